@@ -0,0 +1,129 @@
+// Cycle detection on sequences x0, f(x0), f(f(x0)), ... and projecting a measure that grows by
+// the same amount every period to a step far beyond the detected cycle.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    // steps before the cycle is entered
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    // the step inside the first pass through the cycle that looks the same as step n
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+// Floyd's tortoise and hare, constant memory. Does not return if the sequence never repeats.
+pub fn floyd<S: Clone + PartialEq>(x0: S, f: impl Fn(S) -> S) -> Cycle {
+    let mut tortoise = f(x0.clone());
+    let mut hare = f(f(x0.clone()));
+    while tortoise != hare {
+        tortoise = f(tortoise);
+        hare = f(f(hare));
+    }
+    let mut start = 0;
+    tortoise = x0;
+    while tortoise != hare {
+        tortoise = f(tortoise);
+        hare = f(hare);
+        start += 1;
+    }
+    let mut period = 1;
+    hare = f(tortoise.clone());
+    while tortoise != hare {
+        hare = f(hare);
+        period += 1;
+    }
+    Cycle { start, period }
+}
+
+// Brent's variant, fewer calls to f than floyd
+pub fn brent<S: Clone + PartialEq>(x0: S, f: impl Fn(S) -> S) -> Cycle {
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = x0.clone();
+    let mut hare = f(x0.clone());
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = f(hare);
+        period += 1;
+    }
+    let mut tortoise = x0.clone();
+    let mut hare = x0;
+    for _ in 0..period {
+        hare = f(hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(tortoise);
+        hare = f(hare);
+        start += 1;
+    }
+    Cycle { start, period }
+}
+
+// Remembers the step every key was first seen at, for states too big to compare or clone whole.
+#[derive(Debug, Clone)]
+pub struct Detector<K> {
+    seen: HashMap<K, usize>,
+    steps: usize,
+}
+
+impl<K: Hash + Eq> Default for Detector<K> {
+    fn default() -> Self {
+        Detector { seen: HashMap::new(), steps: 0 }
+    }
+}
+
+impl<K: Hash + Eq> Detector<K> {
+    pub fn new() -> Detector<K> {
+        Default::default()
+    }
+
+    // the key of the next step, starting with step 0
+    pub fn push(&mut self, key: K) -> Option<Cycle> {
+        let step = self.steps;
+        self.steps += 1;
+        self.seen.insert(key, step).map(|start| Cycle { start, period: step - start })
+    }
+}
+
+// measures holds steps 0 up to at least start + period
+pub fn project(cycle: Cycle, measures: &[i64], n: usize) -> i64 {
+    if n < measures.len() {
+        return measures[n];
+    }
+    let gain = measures[cycle.start + cycle.period] - measures[cycle.start];
+    let periods = ((n - cycle.start) / cycle.period) as i64;
+    measures[cycle.equivalent(n)] + periods * gain
+}
+
+// The measure of step n, the states are steps 0, 1, ... None if they run out before step n
+// and before any key repeats.
+pub fn extrapolate<S, K: Hash + Eq>(
+    states: impl IntoIterator<Item = S>,
+    n: usize,
+    key: impl Fn(&S) -> K,
+    measure: impl Fn(&S) -> i64,
+) -> Option<i64> {
+    let mut detector = Detector::new();
+    let mut measures = vec![];
+    for state in states.into_iter().take(n.saturating_add(1)) {
+        measures.push(measure(&state));
+        if let Some(cycle) = detector.push(key(&state)) {
+            return Some(project(cycle, &measures, n));
+        }
+    }
+    measures.get(n).copied()
+}
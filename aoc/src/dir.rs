@@ -0,0 +1,206 @@
+// Compass headings, independent of which way y grows. as_point takes the YAxis of the grid,
+// with YAxis::Up North is the NORTH constant, with YAxis::Down it is one row further up.
+use crate::{Point, YAxis};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir4 {
+    // clockwise, starting north
+    pub const ALL: [Dir4; 4] = [Dir4::North, Dir4::East, Dir4::South, Dir4::West];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    fn from_index(i: usize) -> Dir4 {
+        Dir4::ALL[i % 4]
+    }
+
+    pub fn turn_right(self) -> Dir4 {
+        Dir4::from_index(self.index() + 1)
+    }
+
+    pub fn turn_left(self) -> Dir4 {
+        Dir4::from_index(self.index() + 3)
+    }
+
+    pub fn turn_around(self) -> Dir4 {
+        Dir4::from_index(self.index() + 2)
+    }
+
+    pub fn as_point(self, y_axis: YAxis) -> Point {
+        Dir8::from(self).as_point(y_axis)
+    }
+
+    // accepts UDLR, NESW and ^>v<
+    pub fn from_char(c: char) -> Option<Dir4> {
+        match c {
+            'U' | 'N' | '^' => Some(Dir4::North),
+            'R' | 'E' | '>' => Some(Dir4::East),
+            'D' | 'S' | 'v' => Some(Dir4::South),
+            'L' | 'W' | '<' => Some(Dir4::West),
+            _ => None,
+        }
+    }
+
+    pub fn from_point(p: Point, y_axis: YAxis) -> Option<Dir4> {
+        Dir4::ALL.into_iter().find(|d| d.as_point(y_axis) == p)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    // clockwise, starting north
+    pub const ALL: [Dir8; 8] = [
+        Dir8::North, Dir8::NorthEast, Dir8::East, Dir8::SouthEast,
+        Dir8::South, Dir8::SouthWest, Dir8::West, Dir8::NorthWest,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    fn from_index(i: usize) -> Dir8 {
+        Dir8::ALL[i % 8]
+    }
+
+    // turns by 45 degrees
+    pub fn turn_right(self) -> Dir8 {
+        Dir8::from_index(self.index() + 1)
+    }
+
+    pub fn turn_left(self) -> Dir8 {
+        Dir8::from_index(self.index() + 7)
+    }
+
+    pub fn turn_around(self) -> Dir8 {
+        Dir8::from_index(self.index() + 4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    // diagonal between two perpendicular headings, or the heading itself if both are equal
+    pub fn compose(a: Dir4, b: Dir4) -> Option<Dir8> {
+        let (a, b) = (Dir8::from(a), Dir8::from(b));
+        if a == b {
+            Some(a)
+        } else if a.turn_right().turn_right() == b {
+            Some(a.turn_right())
+        } else if a.turn_left().turn_left() == b {
+            Some(a.turn_left())
+        } else {
+            None
+        }
+    }
+
+    pub fn as_point(self, y_axis: YAxis) -> Point {
+        let p = match self {
+            Dir8::North => crate::NORTH,
+            Dir8::NorthEast => crate::NORTH_EAST,
+            Dir8::East => crate::EAST,
+            Dir8::SouthEast => crate::SOUTH_EAST,
+            Dir8::South => crate::SOUTH,
+            Dir8::SouthWest => crate::SOUTH_WEST,
+            Dir8::West => crate::WEST,
+            Dir8::NorthWest => crate::NORTH_WEST,
+        };
+        match y_axis {
+            YAxis::Up => p,
+            YAxis::Down => Point::new(p.x, -p.y),
+        }
+    }
+
+    pub fn from_point(p: Point, y_axis: YAxis) -> Option<Dir8> {
+        Dir8::ALL.into_iter().find(|d| d.as_point(y_axis) == p)
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(d: Dir4) -> Dir8 {
+        Dir8::ALL[d.index() * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dir4_turns() {
+        assert_eq!(Dir4::ALL.map(Dir4::turn_right), [Dir4::East, Dir4::South, Dir4::West, Dir4::North]);
+        assert_eq!(Dir4::ALL.map(Dir4::turn_left), [Dir4::West, Dir4::North, Dir4::East, Dir4::South]);
+        assert_eq!(Dir4::North.turn_left().turn_around(), Dir4::East);
+        for d in Dir4::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.turn_around());
+        }
+    }
+
+    #[test]
+    fn dir4_points() {
+        assert_eq!(Dir4::from_char('v').unwrap().as_point(YAxis::Down), Point::new(0, 1));
+        assert_eq!(['U', 'E', 'v', '<'].map(Dir4::from_char), Dir4::ALL.map(Some));
+        assert_eq!(Dir4::from_char('x'), None);
+        for y_axis in [YAxis::Up, YAxis::Down] {
+            for d in Dir4::ALL {
+                assert_eq!(Dir4::from_point(d.as_point(y_axis), y_axis), Some(d));
+            }
+        }
+        assert_eq!(Dir4::from_point(Point::new(1, 1), YAxis::Up), None);
+    }
+
+    #[test]
+    fn dir8_turns() {
+        assert_eq!(Dir8::North.turn_right(), Dir8::NorthEast);
+        assert_eq!(Dir8::North.turn_left(), Dir8::NorthWest);
+        assert_eq!(Dir8::SouthEast.turn_around(), Dir8::NorthWest);
+        for d in Dir8::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().is_diagonal(), !d.is_diagonal());
+        }
+        assert_eq!(Dir4::ALL.map(Dir8::from), [Dir8::North, Dir8::East, Dir8::South, Dir8::West]);
+    }
+
+    #[test]
+    fn dir8_compose() {
+        assert_eq!(Dir8::compose(Dir4::South, Dir4::West), Some(Dir8::SouthWest));
+        assert_eq!(Dir8::compose(Dir4::West, Dir4::South), Some(Dir8::SouthWest));
+        assert_eq!(Dir8::compose(Dir4::North, Dir4::East), Some(Dir8::NorthEast));
+        assert_eq!(Dir8::compose(Dir4::East, Dir4::East), Some(Dir8::East));
+        assert_eq!(Dir8::compose(Dir4::North, Dir4::South), None);
+    }
+
+    #[test]
+    fn dir8_points() {
+        assert_eq!(Dir8::NorthEast.as_point(YAxis::Down), Point::new(1, -1));
+        assert_eq!(Dir8::NorthEast.as_point(YAxis::Up), Point::new(1, 1));
+        assert_eq!(Dir8::from_point(Point::new(-1, -1), YAxis::Up), Some(Dir8::SouthWest));
+        assert_eq!(Dir8::from_point(Point::new(-1, -1), YAxis::Down), Some(Dir8::NorthWest));
+        assert_eq!(Dir8::from_point(Point::new(2, 0), YAxis::Up), None);
+        for y_axis in [YAxis::Up, YAxis::Down] {
+            for d in Dir8::ALL {
+                assert_eq!(Dir8::from_point(d.as_point(y_axis), y_axis), Some(d));
+                assert_eq!(d.turn_around().as_point(y_axis), -d.as_point(y_axis));
+            }
+        }
+    }
+}
@@ -0,0 +1,326 @@
+// Hex grids in cube coordinates [x, y, z] with x + y + z = 0, as used by the HEX_* constants.
+// Axial coordinates are [q, r] = [x, z].
+// https://www.redblobgames.com/grids/hexagons/
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+use crate::{bfs, manhattan_hex_cube, FPoint, Point, Vec3};
+use crate::{HEX_E, HEX_NE, HEX_NW, HEX_SE, HEX_SW, HEX_W};
+
+// pointy top directions in clockwise order, flat top grids use the same vectors as HEX_ALT_*
+pub const CLOCKWISE: [Vec3; 6] = [HEX_E, HEX_SE, HEX_SW, HEX_W, HEX_NW, HEX_NE];
+
+pub fn axial_to_cube(Point { x: q, y: r }: Point) -> Vec3 {
+    Vec3::new(q, -q - r, r)
+}
+
+pub fn cube_to_axial(c: Vec3) -> Point {
+    Point::new(c.x, c.z)
+}
+
+pub fn distance(a: Vec3, b: Vec3) -> i64 {
+    manhattan_hex_cube(a, b)
+}
+
+// Offset coordinates [col, row], odd/even says which rows (R) or columns (Q) are shoved over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offset {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+impl Offset {
+    pub fn to_cube(self, Point { x: col, y: row }: Point) -> Vec3 {
+        let [q, r] = match self {
+            Offset::OddR => [col - (row - (row & 1)) / 2, row],
+            Offset::EvenR => [col - (row + (row & 1)) / 2, row],
+            Offset::OddQ => [col, row - (col - (col & 1)) / 2],
+            Offset::EvenQ => [col, row - (col + (col & 1)) / 2],
+        };
+        axial_to_cube(Point::new(q, r))
+    }
+
+    pub fn from_cube(self, c: Vec3) -> Point {
+        let Point { x: q, y: r } = cube_to_axial(c);
+        match self {
+            Offset::OddR => Point::new(q + (r - (r & 1)) / 2, r),
+            Offset::EvenR => Point::new(q + (r + (r & 1)) / 2, r),
+            Offset::OddQ => Point::new(q, r + (q - (q & 1)) / 2),
+            Offset::EvenQ => Point::new(q, r + (q + (q & 1)) / 2),
+        }
+    }
+}
+
+// cells at exactly radius steps, clockwise starting from the north west corner
+pub fn ring(center: Vec3, radius: i64) -> impl Iterator<Item = Vec3> {
+    let start = center + HEX_NW * radius;
+    let sides = if radius == 0 { 0..1 } else { 0..6 };
+    sides.flat_map(move |side| (0..radius.max(1)).map(move |step| (side, step)))
+        .scan(start, move |p, (side, _)| {
+            let current = *p;
+            *p += CLOCKWISE[side];
+            Some(current)
+        })
+}
+
+// rings of growing radius, starting with the center itself
+pub fn spiral(center: Vec3, radius: i64) -> impl Iterator<Item = Vec3> {
+    (0..=radius).flat_map(move |r| ring(center, r))
+}
+
+// all cells within radius steps
+pub fn range(center: Vec3, radius: i64) -> impl Iterator<Item = Vec3> {
+    (-radius..=radius).flat_map(move |dx| {
+        ((-radius).max(-dx - radius)..=radius.min(-dx + radius))
+            .map(move |dz| center + Vec3::new(dx, -dx - dz, dz))
+    })
+}
+
+pub fn round(c: [f64; 3]) -> Vec3 {
+    let mut r = c.map(f64::round);
+    let diff = [(r[0] - c[0]).abs(), (r[1] - c[1]).abs(), (r[2] - c[2]).abs()];
+    if diff[0] > diff[1] && diff[0] > diff[2] {
+        r[0] = -r[1] - r[2];
+    } else if diff[1] > diff[2] {
+        r[1] = -r[0] - r[2];
+    } else {
+        r[2] = -r[0] - r[1];
+    }
+    let [x, y, z] = r.map(|v| v as i64);
+    Vec3::new(x, y, z)
+}
+
+// cells on the straight line from a to b, both included
+pub fn line(a: Vec3, b: Vec3) -> Vec<Vec3> {
+    let n = distance(a, b);
+    // nudge off the exact edges between two hexes so ties round consistently
+    let a = [a.x as f64 + 1e-6, a.y as f64 + 1e-6, a.z as f64 - 2e-6];
+    let b = [b.x as f64 + 1e-6, b.y as f64 + 1e-6, b.z as f64 - 2e-6];
+    (0..=n).map(|i| {
+        let t = if n == 0 { 0.0 } else { i as f64 / n as f64 };
+        round([0, 1, 2].map(|k| a[k] + (b[k] - a[k]) * t))
+    }).collect()
+}
+
+// rotate p around center in steps of 60 degrees, positive steps turn clockwise
+pub fn rotate(p: Vec3, center: Vec3, steps: i64) -> Vec3 {
+    let mut d = p - center;
+    for _ in 0..steps.rem_euclid(6) {
+        d = Vec3::new(-d.z, -d.x, -d.y);
+    }
+    center + d
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Pointy,
+    Flat,
+}
+
+// Maps cells to pixel centers, size is the distance from a center to a corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub orientation: Orientation,
+    pub size: f64,
+    pub origin: FPoint,
+}
+
+impl Layout {
+    pub fn to_pixel(&self, c: Vec3) -> FPoint {
+        let Point { x: q, y: r } = cube_to_axial(c);
+        let (q, r) = (q as f64, r as f64);
+        let sqrt3 = 3f64.sqrt();
+        let [x, y] = match self.orientation {
+            Orientation::Pointy => [sqrt3 * q + sqrt3 / 2.0 * r, 1.5 * r],
+            Orientation::Flat => [1.5 * q, sqrt3 / 2.0 * q + sqrt3 * r],
+        };
+        [self.origin[0] + x * self.size, self.origin[1] + y * self.size]
+    }
+
+    pub fn from_pixel(&self, p: FPoint) -> Vec3 {
+        let x = (p[0] - self.origin[0]) / self.size;
+        let y = (p[1] - self.origin[1]) / self.size;
+        let sqrt3 = 3f64.sqrt();
+        let [q, r] = match self.orientation {
+            Orientation::Pointy => [sqrt3 / 3.0 * x - y / 3.0, 2.0 / 3.0 * y],
+            Orientation::Flat => [2.0 / 3.0 * x, -x / 3.0 + sqrt3 / 3.0 * y],
+        };
+        round([q, -q - r, r])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexGrid<T> {
+    cells: HashMap<Vec3, T>,
+}
+
+impl<T> Default for HexGrid<T> {
+    fn default() -> Self {
+        HexGrid { cells: HashMap::new() }
+    }
+}
+
+impl<T> HexGrid<T> {
+    pub fn new() -> HexGrid<T> {
+        Default::default()
+    }
+
+    pub fn neighbors(&self, p: Vec3) -> impl Iterator<Item = (Vec3, &T)> + '_ {
+        CLOCKWISE.iter().filter_map(move |d| {
+            let nb = p + *d;
+            self.cells.get(&nb).map(|v| (nb, v))
+        })
+    }
+
+    pub fn shortest_path(&self, start: Vec3, goal: Vec3, is_valid_move: impl Fn(&Vec3, &T, &Vec3, &T) -> bool) -> Option<Vec<Vec3>> {
+        bfs(start, goal, |current| {
+            let current_val = &self.cells[&current];
+            self.neighbors(current)
+                .filter(|(next, next_val)| is_valid_move(&current, current_val, next, next_val))
+                .map(|(next, _)| next)
+                .collect::<Vec<_>>()
+        })
+    }
+}
+
+impl<T> Deref for HexGrid<T> {
+    type Target = HashMap<Vec3, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl<T> DerefMut for HexGrid<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cells
+    }
+}
+
+impl<T> FromIterator<(Vec3, T)> for HexGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Vec3, T)>>(iter: I) -> Self {
+        HexGrid { cells: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HEX_DIRECTIONS;
+
+    const ORIGIN: Vec3 = Vec3::new(0, 0, 0);
+
+    #[test]
+    fn offset_round_trips() {
+        for offset in [Offset::OddR, Offset::EvenR, Offset::OddQ, Offset::EvenQ] {
+            for col in -5..=5 {
+                for row in -5..=5 {
+                    let p = Point::new(col, row);
+                    assert_eq!(offset.from_cube(offset.to_cube(p)), p, "{:?} {:?}", offset, p);
+                }
+            }
+            for c in range(ORIGIN, 4) {
+                assert_eq!(offset.to_cube(offset.from_cube(c)), c, "{:?} {:?}", offset, c);
+            }
+        }
+        // odd rows are shoved right, so [0, 1] touches [0, 0] and [1, 0] but not [-1, 0]
+        let odd_r = |col, row| Offset::OddR.to_cube(Point::new(col, row));
+        assert_eq!(distance(odd_r(0, 1), odd_r(0, 0)), 1);
+        assert_eq!(distance(odd_r(0, 1), odd_r(1, 0)), 1);
+        assert_eq!(distance(odd_r(0, 1), odd_r(-1, 0)), 2);
+        // even columns are shoved down, so [1, 0] touches [0, -1] and [0, 0] but not [0, 1]
+        let even_q = |col, row| Offset::EvenQ.to_cube(Point::new(col, row));
+        assert_eq!(distance(even_q(1, 0), even_q(0, -1)), 1);
+        assert_eq!(distance(even_q(1, 0), even_q(0, 0)), 1);
+        assert_eq!(distance(even_q(1, 0), even_q(0, 1)), 2);
+    }
+
+    #[test]
+    fn rings() {
+        assert_eq!(ring(ORIGIN, 0).collect::<Vec<_>>(), vec![ORIGIN]);
+        assert_eq!(ring(ORIGIN, 1).collect::<Vec<_>>(), vec![HEX_NW, HEX_NE, HEX_E, HEX_SE, HEX_SW, HEX_W]);
+        let center = Vec3::new(2, -5, 3);
+        for r in 1..=4 {
+            let cells = ring(center, r).collect::<Vec<_>>();
+            assert_eq!(cells.len() as i64, 6 * r);
+            assert_eq!(cells[0], center + HEX_NW * r);
+            assert!(cells.iter().all(|&c| distance(c, center) == r));
+            // one step apart, all the way around back to the start
+            assert!(cells.iter().zip(cells.iter().cycle().skip(1)).all(|(&a, &b)| distance(a, b) == 1));
+        }
+        assert_eq!(spiral(ORIGIN, 3).count(), range(ORIGIN, 3).count());
+    }
+
+    #[test]
+    fn lines() {
+        let (a, b) = (Vec3::new(-2, 3, -1), Vec3::new(3, -1, -2));
+        let cells = line(a, b);
+        assert_eq!(cells.len() as i64, distance(a, b) + 1);
+        assert_eq!((cells[0], cells[cells.len() - 1]), (a, b));
+        assert!(cells.windows(2).all(|w| distance(w[0], w[1]) == 1));
+        assert_eq!(line(a, a), vec![a]);
+        // halfway to E + NE lies exactly between E and NE, the nudge picks NE from either end
+        let far = HEX_E + HEX_NE;
+        assert_eq!(line(ORIGIN, far), vec![ORIGIN, HEX_NE, far]);
+        assert_eq!(line(far, ORIGIN), vec![far, HEX_NE, ORIGIN]);
+    }
+
+    #[test]
+    fn rotations() {
+        assert_eq!(rotate(HEX_E, ORIGIN, 1), HEX_SE);
+        assert_eq!(rotate(HEX_E, ORIGIN, -1), HEX_NE);
+        assert_eq!(rotate(HEX_E, ORIGIN, 3), HEX_W);
+        for (i, d) in CLOCKWISE.iter().enumerate() {
+            assert_eq!(rotate(HEX_E, ORIGIN, i as i64), *d);
+        }
+        let (p, center) = (Vec3::new(3, -1, -2), Vec3::new(1, 1, -2));
+        assert_eq!(rotate(p, center, 6), p);
+        assert_eq!(rotate(p, center, 2), rotate(p, center, -4));
+        assert_eq!(distance(rotate(p, center, 1), center), distance(p, center));
+    }
+
+    #[test]
+    fn layouts() {
+        for orientation in [Orientation::Pointy, Orientation::Flat] {
+            let layout = Layout { orientation, size: 10.0, origin: [3.0, -7.0] };
+            assert_eq!(layout.to_pixel(ORIGIN), [3.0, -7.0]);
+            for c in spiral(ORIGIN, 4) {
+                assert_eq!(layout.from_pixel(layout.to_pixel(c)), c, "{:?} {:?}", orientation, c);
+                // anywhere well inside the hex maps back to it
+                let [x, y] = layout.to_pixel(c);
+                assert_eq!(layout.from_pixel([x + 4.0, y - 4.0]), c);
+            }
+        }
+        let pointy = Layout { orientation: Orientation::Pointy, size: 1.0, origin: [0.0, 0.0] };
+        let [x, y] = pointy.to_pixel(HEX_E);
+        assert!((x - 3f64.sqrt()).abs() < 1e-9 && y.abs() < 1e-9);
+        let flat = Layout { orientation: Orientation::Flat, size: 1.0, origin: [0.0, 0.0] };
+        let [x, y] = flat.to_pixel(HEX_ALT_S_OF_FLAT);
+        assert!(x.abs() < 1e-9 && (y - 3f64.sqrt()).abs() < 1e-9);
+    }
+
+    // straight down in a flat top layout, [q, r] = [0, 1]
+    const HEX_ALT_S_OF_FLAT: Vec3 = Vec3::new(0, -1, 1);
+
+    #[test]
+    fn shortest_path() {
+        // everything within two steps, the inner ring is a wall with a gap to the south east
+        let grid = range(ORIGIN, 2)
+            .map(|c| (c, distance(c, ORIGIN) != 1 || c == HEX_SE))
+            .collect::<HexGrid<bool>>();
+        let open = |_: &Vec3, _: &bool, _: &Vec3, to: &bool| *to;
+        let goal = HEX_NW * 2;
+        let path = grid.shortest_path(ORIGIN, goal, open).unwrap();
+        assert_eq!((path[0], path[path.len() - 1]), (ORIGIN, goal));
+        assert_eq!(path[1], HEX_SE);
+        assert_eq!(path.len(), 8);
+        assert!(path.windows(2).all(|w| distance(w[0], w[1]) == 1 && grid[&w[1]]));
+        assert_eq!(grid.neighbors(ORIGIN).count(), HEX_DIRECTIONS.len());
+
+        let walled = range(ORIGIN, 2).map(|c| (c, distance(c, ORIGIN) != 1)).collect::<HexGrid<bool>>();
+        assert_eq!(walled.shortest_path(ORIGIN, goal, open), None);
+        assert_eq!(walled.shortest_path(ORIGIN, ORIGIN, open), Some(vec![ORIGIN]));
+    }
+}
@@ -1,12 +1,26 @@
 extern crate vecmath;
 
+pub mod cycle;
+pub mod dir;
+pub mod hex;
+pub mod point;
+pub mod seq;
+pub mod sim;
+pub mod trace;
+
 use std::iter::from_fn;
 use std::collections::{HashMap, BTreeMap, HashSet, BinaryHeap, VecDeque};
 use std::cmp::Reverse;
+use std::hash::Hash;
+use std::cell::Cell;
+use std::ops::Deref;
+use std::marker::PhantomData;
+
+pub use dir::{Dir4, Dir8};
+pub use point::{Point, Vec3};
+pub use trace::{Expansion, Replay};
 
-pub type Point = self::vecmath::Vector2<i64>;
 pub type FPoint = self::vecmath::Vector2<f64>;
-pub type Vec3 = self::vecmath::Vector3<i64>;
 pub type FVec3 = self::vecmath::Vector3<f64>;
 pub type Vec4 = self::vecmath::Vector4<i64>;
 pub type FVec4 = self::vecmath::Vector4<f64>;
@@ -15,50 +29,38 @@ pub type FMat4 = self::vecmath::Matrix4<f64>;
 pub type Mat3 = self::vecmath::Matrix3<i64>;
 
 
-pub use self::vecmath::vec2_add as point_add;
-pub use self::vecmath::vec2_dot as point_dot;
-pub use self::vecmath::vec2_neg as point_neg;
-pub use self::vecmath::vec2_normalized as point_normalize;
-pub use self::vecmath::vec2_scale as point_mul;
-pub use self::vecmath::vec2_square_len as point_square_length;
-pub use self::vecmath::vec2_sub as point_sub;
-pub use self::vecmath::vec3_add as vec_add;
-pub use self::vecmath::vec3_cross as vec_cross;
-pub use self::vecmath::vec3_dot as vec_dot;
-pub use self::vecmath::vec3_neg as vec_neg;
-
-pub const NORTH: Point = [0, 1];
+pub const NORTH: Point = Point::new(0, 1);
 pub const UP: Point = NORTH;
-pub const NORTH_EAST: Point = [1, 1];
+pub const NORTH_EAST: Point = Point::new(1, 1);
 pub const UP_RIGHT: Point = NORTH_EAST;
-pub const EAST: Point = [1, 0];
+pub const EAST: Point = Point::new(1, 0);
 pub const RIGHT: Point = EAST;
-pub const SOUTH_EAST: Point = [1, -1];
+pub const SOUTH_EAST: Point = Point::new(1, -1);
 pub const DOWN_RIGHT: Point = SOUTH_EAST;
-pub const SOUTH: Point = [0, -1];
+pub const SOUTH: Point = Point::new(0, -1);
 pub const DOWN: Point = SOUTH;
-pub const SOUTH_WEST: Point = [-1, -1];
+pub const SOUTH_WEST: Point = Point::new(-1, -1);
 pub const DOWN_LEFT: Point = SOUTH_WEST;
-pub const WEST: Point = [-1, 0];
+pub const WEST: Point = Point::new(-1, 0);
 pub const LEFT: Point = WEST;
-pub const NORTH_WEST: Point = [-1, 1];
+pub const NORTH_WEST: Point = Point::new(-1, 1);
 pub const UP_LEFT: Point = NORTH_WEST;
 
 // Hex directions
 // https://www.redblobgames.com/grids/hexagons/
-pub const HEX_E: Vec3 = [1, -1, 0];
-pub const HEX_W: Vec3 = [-1, 1, 0];
-pub const HEX_SE: Vec3 = [0, -1, 1];
-pub const HEX_SW: Vec3 = [-1, 0, 1];
-pub const HEX_NW: Vec3 = [0, 1, -1];
-pub const HEX_NE: Vec3 = [1, 0, -1];
-
-pub const HEX_ALT_SE: Vec3 = [1, -1, 0];
-pub const HEX_ALT_NW: Vec3 = [-1, 1, 0];
-pub const HEX_ALT_S: Vec3 = [0, -1, 1];
-pub const HEX_ALT_SW: Vec3 = [-1, 0, 1];
-pub const HEX_ALT_N: Vec3 = [0, 1, -1];
-pub const HEX_ALT_NE: Vec3 = [1, 0, -1];
+pub const HEX_E: Vec3 = Vec3::new(1, -1, 0);
+pub const HEX_W: Vec3 = Vec3::new(-1, 1, 0);
+pub const HEX_SE: Vec3 = Vec3::new(0, -1, 1);
+pub const HEX_SW: Vec3 = Vec3::new(-1, 0, 1);
+pub const HEX_NW: Vec3 = Vec3::new(0, 1, -1);
+pub const HEX_NE: Vec3 = Vec3::new(1, 0, -1);
+
+pub const HEX_ALT_SE: Vec3 = Vec3::new(1, -1, 0);
+pub const HEX_ALT_NW: Vec3 = Vec3::new(-1, 1, 0);
+pub const HEX_ALT_S: Vec3 = Vec3::new(0, -1, 1);
+pub const HEX_ALT_SW: Vec3 = Vec3::new(-1, 0, 1);
+pub const HEX_ALT_N: Vec3 = Vec3::new(0, 1, -1);
+pub const HEX_ALT_NE: Vec3 = Vec3::new(1, 0, -1);
 
 pub const DIRECTIONS: [Point; 4] = [NORTH, EAST, SOUTH, WEST];
 pub const DIRECTIONS_INCL_DIAGONALS: [Point; 8] = [
@@ -68,59 +70,142 @@ pub const HEX_DIRECTIONS: [Vec3; 6] = [HEX_E, HEX_W, HEX_SW, HEX_SE, HEX_NW, HEX
 
 pub fn neighbors(p: Point) -> impl Iterator<Item = Point> {
     let mut diter = DIRECTIONS.iter();
-    from_fn(move || diter.next().map(|d| point_add(p, *d)))
+    from_fn(move || diter.next().map(|d| p + *d))
 }
 
 pub fn neighbors_incl_diagonals(p: Point) -> impl Iterator<Item = Point> {
     let mut diter = DIRECTIONS_INCL_DIAGONALS.iter();
-    from_fn(move || diter.next().map(|d| point_add(p, *d)))
+    from_fn(move || diter.next().map(|d| p + *d))
 }
 
 pub fn hex_neighbors(p: Vec3) -> impl Iterator<Item = Vec3> {
     let mut diter = HEX_DIRECTIONS.iter();
-    from_fn(move || diter.next().map(|d| vec_add(p, *d)))
+    from_fn(move || diter.next().map(|d| p + *d))
 }
 
-pub fn point_signum(p: Point) -> Point {
-    [p[0].signum(), p[1].signum()]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YAxis {
+    Down,
+    Up,
 }
 
-pub fn parse_grid<'a, I, J>(lines: I) -> Vec<Vec<char>>
-    where
-        I: IntoIterator<Item = &'a J>,
-        J: AsRef<str> + 'a,
-{
-    lines
-        .into_iter()
-        .map(|x| AsRef::as_ref(x).chars().collect())
-        .collect()
+// Grid contents plus the positions of all extracted markers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedGrid<G> {
+    pub grid: G,
+    pub markers: HashMap<char, Vec<Point>>,
 }
 
-pub fn parse_grid_to<'a, I, J, T>(lines: I, f: fn(char) -> T) -> Vec<Vec<T>>
-    where
-        I: IntoIterator<Item = &'a J>,
-        J: AsRef<str> + 'a,
-{
-    lines
-        .into_iter()
-        .map(|x| AsRef::as_ref(x).chars().map(f).collect())
-        .collect()
+impl<G> ParsedGrid<G> {
+    pub fn marker(&self, c: char) -> Option<Point> {
+        self.markers.get(&c).and_then(|m| m.first().copied())
+    }
 }
 
-pub fn parse_grid_to_sparse<'a, I, J, T>(lines: I, f: fn(char) -> Option<T>) -> HashMap<Point, T>
-    where
-        I: IntoIterator<Item = &'a J>,
-        J: AsRef<str> + 'a,
-{
-    let mut grid = HashMap::new();
-    for (y, line) in lines.into_iter().enumerate() {
-        for (x, c) in AsRef::as_ref(line).chars().enumerate() {
-            if let Some(t) = f(c) {
-                grid.insert([x as i64, y as i64], t);
+// Builder for reading character grids. The origin is the coordinate of the first character
+// of the first line, with YAxis::Up every following line is one lower, matching NORTH = [0, 1].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridParser {
+    y_axis: YAxis,
+    origin: Point,
+    skip: Vec<char>,
+    markers: Vec<(char, char)>,
+}
+
+impl Default for GridParser {
+    fn default() -> Self {
+        GridParser {
+            y_axis: YAxis::Down,
+            origin: Point::new(0, 0),
+            skip: vec![],
+            markers: vec![],
+        }
+    }
+}
+
+impl GridParser {
+    pub fn new() -> GridParser {
+        Default::default()
+    }
+
+    pub fn y_up(mut self) -> Self {
+        self.y_axis = YAxis::Up;
+        self
+    }
+
+    pub fn y_down(mut self) -> Self {
+        self.y_axis = YAxis::Down;
+        self
+    }
+
+    pub fn origin(mut self, origin: Point) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn skip(mut self, c: char) -> Self {
+        self.skip.push(c);
+        self
+    }
+
+    // remember where c occurs and parse it as if it was replacement
+    pub fn marker(mut self, c: char, replacement: char) -> Self {
+        self.markers.push((c, replacement));
+        self
+    }
+
+    pub fn position(&self, x: usize, y: usize) -> Point {
+        match self.y_axis {
+            YAxis::Down => Point::new(self.origin.x + x as i64, self.origin.y + y as i64),
+            YAxis::Up => Point::new(self.origin.x + x as i64, self.origin.y - y as i64),
+        }
+    }
+
+    fn cells<'a>(&'a self, input: &'a str, markers: &'a mut HashMap<char, Vec<Point>>) -> impl Iterator<Item = (Point, char)> + 'a {
+        input.lines().enumerate().flat_map(move |(y, line)| {
+            line.chars().enumerate().map(move |(x, c)| (self.position(x, y), c))
+        }).filter(|(_, c)| !self.skip.contains(c)).map(move |(p, c)| {
+            match self.markers.iter().find(|(m, _)| *m == c) {
+                Some(&(m, replacement)) => {
+                    markers.entry(m).or_default().push(p);
+                    (p, replacement)
+                }
+                None => (p, c),
             }
+        })
+    }
+
+    pub fn parse_sparse<T>(&self, input: &str, f: impl Fn(char) -> Option<T>) -> ParsedGrid<HashMap<Point, T>> {
+        let mut markers = HashMap::new();
+        let grid = self.cells(input, &mut markers).filter_map(|(p, c)| f(c).map(|t| (p, t))).collect();
+        ParsedGrid { grid, markers }
+    }
+
+    // Dense grids are indexed from [0, 0], so the origin is ignored: the first character of the
+    // top line (YAxis::Down) or of the bottom line (YAxis::Up) is [0, 0] and the markers move
+    // along. Skipped characters and short lines are filled with T::default().
+    pub fn parse_dense<T>(&self, input: &str, f: impl Fn(char) -> T) -> ParsedGrid<Vec<Vec<T>>>
+        where
+            T: Clone + Default,
+    {
+        let mut markers = HashMap::new();
+        let cells = self.cells(input, &mut markers).collect::<Vec<_>>();
+        let shift = match self.y_axis {
+            YAxis::Down => self.position(0, 0),
+            YAxis::Up => self.position(0, input.lines().count().saturating_sub(1)),
+        };
+        let width = cells.iter().map(|(p, _)| p.x - shift.x + 1).max().unwrap_or(0) as usize;
+        let height = cells.iter().map(|(p, _)| p.y - shift.y + 1).max().unwrap_or(0) as usize;
+        let mut grid = vec![vec![T::default(); width]; height];
+        for (p, c) in cells {
+            let Point { x, y } = p - shift;
+            grid[y as usize][x as usize] = f(c);
         }
+        for positions in markers.values_mut() {
+            positions.iter_mut().for_each(|p| *p -= shift);
+        }
+        ParsedGrid { grid, markers }
     }
-    grid
 }
 
 pub struct GridIteratorHelper {
@@ -132,11 +217,11 @@ impl Iterator for GridIteratorHelper {
     type Item = Point;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some([x, y]) = self.curr {
-            let c = if x < self.extents.1[0] {
-                Some([x + 1, y])
-            } else if y < self.extents.1[1] {
-                Some([self.extents.0[0], y + 1])
+        if let Some(Point { x, y }) = self.curr {
+            let c = if x < self.extents.1.x {
+                Some(Point::new(x + 1, y))
+            } else if y < self.extents.1.y {
+                Some(Point::new(self.extents.0.x, y + 1))
             } else {
                 None
             };
@@ -149,20 +234,141 @@ impl Iterator for GridIteratorHelper {
     }
 }
 
-pub trait Grid<T>
+// Read-only access to a grid, also implemented by borrowed windows into another grid
+pub trait GridView<T>
     where
         T: PartialEq + Copy,
 {
     fn get_value(&self, pos: Point) -> Option<T>;
-    fn set_value(&mut self, pos: Point, value: T);
     fn extents(&self) -> (Point, Point);
     fn points(&self) -> GridIteratorHelper {
         let extents = self.extents();
         GridIteratorHelper {
-            extents: extents,
+            extents,
             curr: Some(extents.0)
         }
     }
+    fn contains(&self, pos: Point) -> bool {
+        let (min, max) = self.extents();
+        (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y)
+    }
+    // Neighbouring cells that hold a value. These iterators are concrete types so nothing is
+    // boxed per call, which is also why they need a sized grid.
+    fn neighbors4(&self, pos: Point) -> Cells<'_, T, Self, std::array::IntoIter<Point, 4>>
+        where
+            Self: Sized,
+    {
+        Cells::new(self, DIRECTIONS.map(|d| pos + d).into_iter())
+    }
+    fn neighbors8(&self, pos: Point) -> Cells<'_, T, Self, std::array::IntoIter<Point, 8>>
+        where
+            Self: Sized,
+    {
+        Cells::new(self, DIRECTIONS_INCL_DIAGONALS.map(|d| pos + d).into_iter())
+    }
+    // cells from pos (exclusive) in steps of dir up to the first position without a value, none
+    // for a zero dir
+    fn ray(&self, pos: Point, dir: Point) -> Ray<'_, T, Self>
+        where
+            Self: Sized,
+    {
+        Ray { grid: self, pos, dir, value: PhantomData }
+    }
+    // the cells of a row or column that hold a value, extents() is called once per row or column
+    fn row(&self, y: i64) -> Cells<'_, T, Self, Line>
+        where
+            Self: Sized,
+    {
+        let (min, max) = self.extents();
+        Cells::new(self, Line { next: Point::new(min.x, y), dir: EAST, left: max.x - min.x + 1 })
+    }
+    fn col(&self, x: i64) -> Cells<'_, T, Self, Line>
+        where
+            Self: Sized,
+    {
+        let (min, max) = self.extents();
+        Cells::new(self, Line { next: Point::new(x, min.y), dir: NORTH, left: max.y - min.y + 1 })
+    }
+}
+
+// the positions that hold a value, with their values, see GridView::neighbors4
+pub struct Cells<'a, T, G: ?Sized, I> {
+    grid: &'a G,
+    positions: I,
+    value: PhantomData<T>,
+}
+
+impl<'a, T, G: ?Sized, I> Cells<'a, T, G, I> {
+    fn new(grid: &'a G, positions: I) -> Cells<'a, T, G, I> {
+        Cells { grid, positions, value: PhantomData }
+    }
+}
+
+impl<T, G, I> Iterator for Cells<'_, T, G, I>
+    where
+        T: PartialEq + Copy,
+        G: GridView<T> + ?Sized,
+        I: Iterator<Item = Point>,
+{
+    type Item = (Point, T);
+
+    fn next(&mut self) -> Option<(Point, T)> {
+        let grid = self.grid;
+        self.positions.find_map(|p| grid.get_value(p).map(|v| (p, v)))
+    }
+}
+
+// left positions starting at next in steps of dir
+#[derive(Debug, Clone)]
+pub struct Line {
+    next: Point,
+    dir: Point,
+    left: i64,
+}
+
+impl Iterator for Line {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        if self.left <= 0 {
+            return None;
+        }
+        let p = self.next;
+        self.next += self.dir;
+        self.left -= 1;
+        Some(p)
+    }
+}
+
+pub struct Ray<'a, T, G: ?Sized> {
+    grid: &'a G,
+    pos: Point,
+    dir: Point,
+    value: PhantomData<T>,
+}
+
+impl<T, G> Iterator for Ray<'_, T, G>
+    where
+        T: PartialEq + Copy,
+        G: GridView<T> + ?Sized,
+{
+    type Item = (Point, T);
+
+    fn next(&mut self) -> Option<(Point, T)> {
+        // a ray that doesn't move would never leave the grid
+        if self.dir == Point::new(0, 0) {
+            return None;
+        }
+        self.pos += self.dir;
+        self.grid.get_value(self.pos).map(|v| (self.pos, v))
+    }
+}
+
+pub trait Grid<T>: GridView<T>
+    where
+        T: PartialEq + Copy,
+{
+    fn set_value(&mut self, pos: Point, value: T);
     fn flip_horizontal(&mut self);
     fn flip_vertical(&mut self);
     fn transpose(&mut self);
@@ -180,30 +386,82 @@ pub trait Grid<T>
     }
 }
 
+// Window into another grid, coordinates stay those of the underlying grid
+pub struct SubGrid<'a, T, G>
+    where
+        T: PartialEq + Copy,
+        G: GridView<T> + ?Sized,
+{
+    grid: &'a G,
+    extents: (Point, Point),
+    value: std::marker::PhantomData<T>,
+}
 
-impl<S: ::std::hash::BuildHasher, T> Grid<T> for HashMap<Point, T, S>
+impl<'a, T, G> SubGrid<'a, T, G>
+    where
+        T: PartialEq + Copy,
+        G: GridView<T> + ?Sized,
+{
+    pub fn new(grid: &'a G, min: Point, max: Point) -> SubGrid<'a, T, G> {
+        let (gmin, gmax) = grid.extents();
+        SubGrid {
+            grid,
+            extents: (
+                Point::new(min.x.max(gmin.x), min.y.max(gmin.y)),
+                Point::new(max.x.min(gmax.x), max.y.min(gmax.y)),
+            ),
+            value: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T, G> GridView<T> for SubGrid<'_, T, G>
+    where
+        T: PartialEq + Copy,
+        G: GridView<T> + ?Sized,
+{
+    fn get_value(&self, pos: Point) -> Option<T> {
+        if self.contains(pos) {
+            self.grid.get_value(pos)
+        } else {
+            None
+        }
+    }
+    fn extents(&self) -> (Point, Point) {
+        self.extents
+    }
+}
+
+
+impl<S: ::std::hash::BuildHasher, T> GridView<T> for HashMap<Point, T, S>
     where
         T: Clone + Copy + Default + PartialEq,
 {
     fn get_value(&self, pos: Point) -> Option<T> {
         self.get(&pos).copied()
     }
+    fn extents(&self) -> (Point, Point) {
+        let min_x = self.keys().map(|p| p.x).min().unwrap_or(0);
+        let min_y = self.keys().map(|p| p.y).min().unwrap_or(0);
+        let max_x = self.keys().map(|p| p.x).max().unwrap_or(0);
+        let max_y = self.keys().map(|p| p.y).max().unwrap_or(0);
+        (Point::new(min_x, min_y), Point::new(max_x, max_y))
+    }
+}
+
+impl<S: ::std::hash::BuildHasher, T> Grid<T> for HashMap<Point, T, S>
+    where
+        T: Clone + Copy + Default + PartialEq,
+{
     fn set_value(&mut self, pos: Point, value: T) {
         *self.entry(pos).or_insert(value) = value;
     }
-    fn extents(&self) -> (Point, Point) {
-        let min_x = self.iter().map(|(p, _v)| p[0]).min().unwrap_or(0);
-        let min_y = self.iter().map(|(p, _v)| p[1]).min().unwrap_or(0);
-        let max_x = self.iter().map(|(p, _v)| p[0]).max().unwrap_or(0);
-        let max_y = self.iter().map(|(p, _v)| p[1]).max().unwrap_or(0);
-        ([min_x, min_y], [max_x, max_y])
-    }
     fn flip_horizontal(&mut self) {
-        let ([min_x, _min_y], [max_x, _max_y]) = self.extents();
+        let (Point { x: min_x, y: _min_y }, Point { x: max_x, y: _max_y }) = self.extents();
         let mut new_grid = HashMap::new();
-        for ([x, y], v) in self.iter() {
+        for (Point { x, y }, v) in self.iter() {
             let new_x = max_x - (x - min_x);
-            new_grid.insert([new_x, *y], *v);
+            new_grid.insert(Point::new(new_x, *y), *v);
         }
         self.clear();
         for (k, v) in new_grid {
@@ -211,11 +469,11 @@ impl<S: ::std::hash::BuildHasher, T> Grid<T> for HashMap<Point, T, S>
         }
     }
     fn flip_vertical(&mut self) {
-        let ([_min_x, min_y], [_max_x, max_y]) = self.extents();
+        let (Point { x: _min_x, y: min_y }, Point { x: _max_x, y: max_y }) = self.extents();
         let mut new_grid = HashMap::new();
-        for ([x, y], v) in self.iter() {
+        for (Point { x, y }, v) in self.iter() {
             let new_y = max_y - (y - min_y);
-            new_grid.insert([*x, new_y], *v);
+            new_grid.insert(Point::new(*x, new_y), *v);
         }
         self.clear();
         for (k, v) in new_grid {
@@ -224,8 +482,8 @@ impl<S: ::std::hash::BuildHasher, T> Grid<T> for HashMap<Point, T, S>
     }
     fn transpose(&mut self) {
         let mut new_grid = HashMap::new();
-        for ([x, y], v) in self.iter() {
-            new_grid.insert([*y, *x], *v);
+        for (Point { x, y }, v) in self.iter() {
+            new_grid.insert(Point::new(*y, *x), *v);
         }
         self.clear();
         for (k, v) in new_grid {
@@ -234,29 +492,35 @@ impl<S: ::std::hash::BuildHasher, T> Grid<T> for HashMap<Point, T, S>
     }
 }
 
-impl<T> Grid<T> for BTreeMap<Point, T>
+impl<T> GridView<T> for BTreeMap<Point, T>
     where
         T: Clone + Copy + Default + PartialEq,
 {
     fn get_value(&self, pos: Point) -> Option<T> {
         self.get(&pos).copied()
     }
+    fn extents(&self) -> (Point, Point) {
+        let min_x = self.keys().map(|p| p.x).min().unwrap_or(0);
+        let min_y = self.keys().map(|p| p.y).min().unwrap_or(0);
+        let max_x = self.keys().map(|p| p.x).max().unwrap_or(0);
+        let max_y = self.keys().map(|p| p.y).max().unwrap_or(0);
+        (Point::new(min_x, min_y), Point::new(max_x, max_y))
+    }
+}
+
+impl<T> Grid<T> for BTreeMap<Point, T>
+    where
+        T: Clone + Copy + Default + PartialEq,
+{
     fn set_value(&mut self, pos: Point, value: T) {
         *self.entry(pos).or_insert(value) = value;
     }
-    fn extents(&self) -> (Point, Point) {
-        let min_x = self.iter().map(|(p, _v)| p[0]).min().unwrap_or(0);
-        let min_y = self.iter().map(|(p, _v)| p[1]).min().unwrap_or(0);
-        let max_x = self.iter().map(|(p, _v)| p[0]).max().unwrap_or(0);
-        let max_y = self.iter().map(|(p, _v)| p[1]).max().unwrap_or(0);
-        ([min_x, min_y], [max_x, max_y])
-    }
     fn flip_horizontal(&mut self) {
-        let ([min_x, _min_y], [max_x, _max_y]) = self.extents();
+        let (Point { x: min_x, y: _min_y }, Point { x: max_x, y: _max_y }) = self.extents();
         let mut new_grid = HashMap::new();
-        for ([x, y], v) in self.iter() {
+        for (Point { x, y }, v) in self.iter() {
             let new_x = max_x - (x - min_x);
-            new_grid.insert([new_x, *y], *v);
+            new_grid.insert(Point::new(new_x, *y), *v);
         }
         self.clear();
         for (k, v) in new_grid {
@@ -264,11 +528,11 @@ impl<T> Grid<T> for BTreeMap<Point, T>
         }
     }
     fn flip_vertical(&mut self) {
-        let ([_min_x, min_y], [_max_x, max_y]) = self.extents();
+        let (Point { x: _min_x, y: min_y }, Point { x: _max_x, y: max_y }) = self.extents();
         let mut new_grid = HashMap::new();
-        for ([x, y], v) in self.iter() {
+        for (Point { x, y }, v) in self.iter() {
             let new_y = max_y - (y - min_y);
-            new_grid.insert([*x, new_y], *v);
+            new_grid.insert(Point::new(*x, new_y), *v);
         }
         self.clear();
         for (k, v) in new_grid {
@@ -277,8 +541,8 @@ impl<T> Grid<T> for BTreeMap<Point, T>
     }
     fn transpose(&mut self) {
         let mut new_grid = HashMap::new();
-        for ([x, y], v) in self.iter() {
-            new_grid.insert([*y, *x], *v);
+        for (Point { x, y }, v) in self.iter() {
+            new_grid.insert(Point::new(*y, *x), *v);
         }
         self.clear();
         for (k, v) in new_grid {
@@ -287,12 +551,12 @@ impl<T> Grid<T> for BTreeMap<Point, T>
     }
 }
 
-impl<T> Grid<T> for Vec<Vec<T>>
+impl<T> GridView<T> for Vec<Vec<T>>
     where
         T: Clone + Copy + Default + PartialEq,
 {
     fn get_value(&self, pos: Point) -> Option<T> {
-        let [x, y] = pos;
+        let Point { x, y } = pos;
         if let Some(line) = self.get(y as usize) {
             if let Some(p) = line.get(x as usize) {
                 return Some(*p)
@@ -301,8 +565,23 @@ impl<T> Grid<T> for Vec<Vec<T>>
         None
     }
 
+    fn extents(&self) -> (Point, Point) {
+        if !self.is_empty() && !self[0].is_empty() {
+            return (
+                Point::new(0, 0),
+                Point::new((self[0].len()-1) as i64, (self.len()-1) as i64),
+            );
+        }
+        (Point::new(0, 0),Point::new(0, 0))
+    }
+}
+
+impl<T> Grid<T> for Vec<Vec<T>>
+    where
+        T: Clone + Copy + Default + PartialEq,
+{
     fn set_value(&mut self, pos: Point, value: T){
-        let [x, y] = pos;
+        let Point { x, y } = pos;
         if let Some(line) = self.get_mut(y as usize) {
             if let Some(p) = line.get_mut(x as usize) {
                 *p = value
@@ -310,18 +589,8 @@ impl<T> Grid<T> for Vec<Vec<T>>
         }
     }
 
-    fn extents(&self) -> (Point, Point) {
-        if !self.is_empty() && !self[0].is_empty() {
-            return (
-                [0,0],
-                [(self[0].len()-1) as i64, (self.len()-1) as i64],
-            );
-        }
-        ([0,0],[0,0])
-    }
-
     fn flip_horizontal(&mut self) {
-        let ([minx, miny], [maxx, maxy]) = self.extents();
+        let (Point { x: minx, y: miny }, Point { x: maxx, y: maxy }) = self.extents();
         let mut new_vec = self.clone();
         for y in miny..=maxy {
             for x in minx..=maxx {
@@ -334,7 +603,7 @@ impl<T> Grid<T> for Vec<Vec<T>>
     }
 
     fn flip_vertical(&mut self) {
-        let ([minx, miny], [maxx, maxy]) = self.extents();
+        let (Point { x: minx, y: miny }, Point { x: maxx, y: maxy }) = self.extents();
         let mut new_vec = self.clone();
         for y in miny..=maxy {
             for x in minx..=maxx {
@@ -347,7 +616,7 @@ impl<T> Grid<T> for Vec<Vec<T>>
     }
 
     fn transpose(&mut self) {
-        let ([min_x, min_y], [max_x, max_y]) = self.extents();
+        let (Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y }) = self.extents();
         let width = (max_x - min_x + 1) as usize;
         let height = (max_y - min_y + 1) as usize;
         // Make a vec with the transposed dimensions
@@ -367,29 +636,161 @@ impl<T> Grid<T> for Vec<Vec<T>>
     }
 }
 
+// HashMap backed grid that keeps its bounding box up to date on insert. Removing a cell on the
+// border only marks the box stale, it is recomputed on the next extents call.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Cell<Option<(Point, Point)>>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid { cells: HashMap::new(), bounds: Cell::new(None) }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        Default::default()
+    }
+
+    pub fn insert(&mut self, pos: Point, value: T) -> Option<T> {
+        let old = self.cells.insert(pos, value);
+        if self.cells.len() == 1 {
+            self.bounds.set(Some((pos, pos)));
+        } else if let Some((min, max)) = self.bounds.get() {
+            self.bounds.set(Some((
+                Point::new(min.x.min(pos.x), min.y.min(pos.y)),
+                Point::new(max.x.max(pos.x), max.y.max(pos.y)),
+            )));
+        }
+        old
+    }
+
+    pub fn remove(&mut self, pos: &Point) -> Option<T> {
+        let old = self.cells.remove(pos);
+        if old.is_some() {
+            if let Some((min, max)) = self.bounds.get() {
+                if pos.x == min.x || pos.x == max.x || pos.y == min.y || pos.y == max.y {
+                    self.bounds.set(None);
+                }
+            }
+        }
+        old
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds.set(None);
+    }
+
+    pub fn extents(&self) -> (Point, Point) {
+        if let Some(bounds) = self.bounds.get() {
+            return bounds;
+        }
+        if self.cells.is_empty() {
+            return (Point::new(0, 0), Point::new(0, 0));
+        }
+        let bounds = self.cells.keys().fold((Point::new(i64::MAX, i64::MAX), Point::new(i64::MIN, i64::MIN)), |(min, max), p| {
+            (Point::new(min.x.min(p.x), min.y.min(p.y)), Point::new(max.x.max(p.x), max.y.max(p.y)))
+        });
+        self.bounds.set(Some(bounds));
+        bounds
+    }
+
+    pub fn into_inner(self) -> HashMap<Point, T> {
+        self.cells
+    }
+
+    fn remap(&mut self, f: impl Fn(Point) -> Point) {
+        let cells = std::mem::take(&mut self.cells);
+        self.clear();
+        for (p, v) in cells {
+            self.insert(f(p), v);
+        }
+    }
+}
+
+impl<T> Deref for SparseGrid<T> {
+    type Target = HashMap<Point, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (p, v) in iter {
+            grid.insert(p, v);
+        }
+        grid
+    }
+}
+
+impl<T> From<HashMap<Point, T>> for SparseGrid<T> {
+    fn from(cells: HashMap<Point, T>) -> Self {
+        SparseGrid { cells, bounds: Cell::new(None) }
+    }
+}
+
+impl<T> GridView<T> for SparseGrid<T>
+    where
+        T: Clone + Copy + Default + PartialEq,
+{
+    fn get_value(&self, pos: Point) -> Option<T> {
+        self.cells.get(&pos).copied()
+    }
+    fn extents(&self) -> (Point, Point) {
+        SparseGrid::extents(self)
+    }
+}
+
+impl<T> Grid<T> for SparseGrid<T>
+    where
+        T: Clone + Copy + Default + PartialEq,
+{
+    fn set_value(&mut self, pos: Point, value: T) {
+        self.insert(pos, value);
+    }
+    fn flip_horizontal(&mut self) {
+        let (Point { x: min_x, y: _min_y }, Point { x: max_x, y: _max_y }) = self.extents();
+        self.remap(|Point { x, y }| Point::new(max_x - (x - min_x), y));
+    }
+    fn flip_vertical(&mut self) {
+        let (Point { x: _min_x, y: min_y }, Point { x: _max_x, y: max_y }) = self.extents();
+        self.remap(|Point { x, y }| Point::new(x, max_y - (y - min_y)));
+    }
+    fn transpose(&mut self) {
+        self.remap(|Point { x, y }| Point::new(y, x));
+    }
+}
+
 pub fn manhattan(n: Point, goal: Point) -> i64 {
-    (goal[0] - n[0]).abs() + (goal[1] - n[1]).abs()
+    n.manhattan(goal)
 }
 
 pub fn manhattan_circumference_plus(p: &Point, manhattan: i64, add: i64) -> Vec<Point> {
-    ((p[1] - manhattan - add)..=(p[1] + manhattan + add)).into_iter().fold(Vec::<Point>::new(), |mut acc, y| {
-        let rest = manhattan - (p[1] - y).abs() + add;
-        acc.push([p[0] - rest, y]);
-        acc.push([p[0] + rest, y]);
+    ((p.y - manhattan - add)..=(p.y + manhattan + add)).fold(Vec::<Point>::new(), |mut acc, y| {
+        let rest = manhattan - (p.y - y).abs() + add;
+        acc.push(Point::new(p.x - rest, y));
+        acc.push(Point::new(p.x + rest, y));
         acc
     })
 }
 
 pub fn manhattan_circumference_contains_y(p: &Point, manhattan: i64, y: i64) -> Vec<Point> {
-    let rest = manhattan - (p[1] - y).abs();
-    ((p[0] - rest ) ..= (p[0] + rest)).into_iter().fold(Vec::<Point>::new(), |mut acc, x| {
-        acc.push([x, y]);
+    let rest = manhattan - (p.y - y).abs();
+    ((p.x - rest)..=(p.x + rest)).fold(Vec::<Point>::new(), |mut acc, x| {
+        acc.push(Point::new(x, y));
         acc
     })
 }
 
 pub fn manhattan_vec3(n: Vec3, goal: Vec3) -> i64 {
-    (goal[0] - n[0]).abs() + (goal[1] - n[1]).abs() + (goal[2] - n[2]).abs()
+    n.manhattan(goal)
 }
 
 pub fn manhattan_vec4(n: Vec4, goal: Vec4) -> i64 {
@@ -400,15 +801,48 @@ pub fn manhattan_vec4(n: Vec4, goal: Vec4) -> i64 {
 }
 
 pub fn manhattan_hex_cube(n: Vec3, goal: Vec3) -> i64 {
-    ((goal[0] - n[0]).abs() + (goal[1] - n[1]).abs() + (goal[2] - n[2]).abs()) / 2
+    n.manhattan(goal) / 2
 }
 
 pub fn astar_grid<T>(
-    grid: &dyn Grid<T>,
+    grid: &dyn GridView<T>,
+    is_node: fn(&Point, &T) -> bool,
+    get_edge_cost: fn(&Point, &T, &Point, &T) -> Option<i64>,
+    start: Point,
+    goal: Point,
+) -> Option<(i64, Vec<Point>)>
+    where
+        T: PartialEq + Copy,
+{
+    astar_grid_inner(grid, is_node, get_edge_cost, start, goal, None)
+}
+
+// cost and path of a search, if it found one
+pub type Found = Option<(i64, Vec<Point>)>;
+
+// astar_grid that also returns every expansion in the order it happened
+pub fn astar_grid_traced<T>(
+    grid: &dyn GridView<T>,
     is_node: fn(&Point, &T) -> bool,
     get_edge_cost: fn(&Point, &T, &Point, &T) -> Option<i64>,
     start: Point,
     goal: Point,
+) -> (Found, Vec<Expansion<Point>>)
+    where
+        T: PartialEq + Copy,
+{
+    let mut trace = vec![];
+    let found = astar_grid_inner(grid, is_node, get_edge_cost, start, goal, Some(&mut trace));
+    (found, trace)
+}
+
+fn astar_grid_inner<T>(
+    grid: &dyn GridView<T>,
+    is_node: fn(&Point, &T) -> bool,
+    get_edge_cost: fn(&Point, &T, &Point, &T) -> Option<i64>,
+    start: Point,
+    goal: Point,
+    mut trace: Option<&mut Vec<Expansion<Point>>>,
 ) -> Option<(i64, Vec<Point>)>
     where
         T: PartialEq + Copy,
@@ -421,6 +855,9 @@ pub fn astar_grid<T>(
     fscore.insert(start, manhattan(start, goal));
     frontier.push(Reverse((manhattan(start, goal), start)));
     while let Some(Reverse((_est, current))) = frontier.pop() {
+        if let Some(trace) = trace.as_mut() {
+            trace.push(Expansion::new(current));
+        }
         if current == goal {
             let mut path = vec![goal];
             let mut curr = goal;
@@ -433,17 +870,21 @@ pub fn astar_grid<T>(
         let g = *gscore.entry(current).or_insert(i64::MAX);
         let curr_val = grid.get_value(current).unwrap();
         for nb in neighbors(current) {
-            if let Some(value) = grid.get_value(nb) {
-                if is_node(&nb, &value) {
-                    if let Some(edge_cost) = get_edge_cost(&current, &curr_val, &nb, &value) {
-                        let new_g = g + edge_cost;
-                        let nb_g = gscore.entry(nb).or_insert(i64::MAX);
-                        if new_g < *nb_g {
-                            came_from.insert(nb, current);
-                            *nb_g = new_g;
-                            let new_f = new_g + manhattan(goal, nb);
-                            *fscore.entry(nb).or_insert(i64::MAX) = new_f;
-                            frontier.push(Reverse((new_f, nb)));
+            let Some(value) = grid.get_value(nb) else {
+                continue;
+            };
+            if is_node(&nb, &value) {
+                if let Some(edge_cost) = get_edge_cost(&current, &curr_val, &nb, &value) {
+                    let new_g = g + edge_cost;
+                    let nb_g = gscore.entry(nb).or_insert(i64::MAX);
+                    if new_g < *nb_g {
+                        came_from.insert(nb, current);
+                        *nb_g = new_g;
+                        let new_f = new_g + manhattan(goal, nb);
+                        *fscore.entry(nb).or_insert(i64::MAX) = new_f;
+                        frontier.push(Reverse((new_f, nb)));
+                        if let Some(trace) = trace.as_mut() {
+                            trace.last_mut().unwrap().queued.push(nb);
                         }
                     }
                 }
@@ -455,7 +896,7 @@ pub fn astar_grid<T>(
 
 
 pub fn dijkstra_grid<T>(
-    grid: &dyn Grid<T>,
+    grid: &dyn GridView<T>,
     is_node: fn(&Point, &T) -> bool,
     get_edge_cost: fn(&Point, &T, &Point, &T) -> Option<i64>,
     start: Point,
@@ -483,16 +924,17 @@ pub fn dijkstra_grid<T>(
         }
         let curr_val = grid.get_value(current).unwrap();
         for nb in neighbors(current) {
+            let Some(value) = grid.get_value(nb) else {
+                continue;
+            };
             if visited.contains(&nb) {
                 continue;
             }
-            if let Some(value) = grid.get_value(nb) {
-                if is_node(&nb, &value) {
-                    if let Some(edge_cost) = get_edge_cost(&current, &curr_val, &nb, &value) {
-                        let new_score = score + edge_cost;
-                        came_from.insert(nb, current);
-                        frontier.push(Reverse((new_score, nb)));
-                    }
+            if is_node(&nb, &value) {
+                if let Some(edge_cost) = get_edge_cost(&current, &curr_val, &nb, &value) {
+                    let new_score = score + edge_cost;
+                    came_from.insert(nb, current);
+                    frontier.push(Reverse((new_score, nb)));
                 }
             }
         }
@@ -501,43 +943,184 @@ pub fn dijkstra_grid<T>(
     None
 }
 
-pub fn bfs_grid<T>(
-    grid: &dyn Grid<T>,
-    is_valid_move: fn(&Point, &T, &Point, &T) -> bool,
-    start: Point,
-    goal: Point,
-) -> Option<Vec<Point>>
+// Breadth first search over any node type, the path includes start and goal.
+pub fn bfs<N, I>(start: N, goal: N, next: impl FnMut(N) -> I) -> Option<Vec<N>>
     where
-        T: PartialEq + Copy,
+        N: Copy + Eq + Hash,
+        I: IntoIterator<Item = N>,
 {
-    let mut q = VecDeque::<Point>::new();
-    let mut visited: HashSet<Point> = HashSet::new();
+    bfs_inner(start, goal, next, None)
+}
+
+// bfs that also returns every expansion in the order it happened
+pub fn bfs_traced<N, I>(start: N, goal: N, next: impl FnMut(N) -> I) -> (Option<Vec<N>>, Vec<Expansion<N>>)
+    where
+        N: Copy + Eq + Hash,
+        I: IntoIterator<Item = N>,
+{
+    let mut trace = vec![];
+    let found = bfs_inner(start, goal, next, Some(&mut trace));
+    (found, trace)
+}
+
+fn bfs_inner<N, I>(start: N, goal: N, mut next: impl FnMut(N) -> I, mut trace: Option<&mut Vec<Expansion<N>>>) -> Option<Vec<N>>
+    where
+        N: Copy + Eq + Hash,
+        I: IntoIterator<Item = N>,
+{
+    let mut q = VecDeque::<N>::new();
     let mut came_from = HashMap::new();
-    visited.insert(start);
+    came_from.insert(start, start);
     q.push_back(start);
     while let Some(current) = q.pop_front() {
+        if let Some(trace) = trace.as_mut() {
+            trace.push(Expansion::new(current));
+        }
         if current == goal {
             let mut path = vec![goal];
-            let mut curr = came_from[&current];
+            let mut curr = goal;
             while curr != start {
                 curr = came_from[&curr];
                 path.push(curr);
             }
             return Some(path.into_iter().rev().collect())
         }
-        let current_val = grid.get_value(current).unwrap();
-        for next in neighbors(current) {
-            if visited.contains(&next) {
-                continue;
-            }
-            if let Some(next_val) = grid.get_value(next) {
-                if is_valid_move(&current, &current_val, &next, &next_val) {
-                    visited.insert(next);
-                    came_from.insert(next, current);
-                    q.push_back(next);
+        for nb in next(current) {
+            if let std::collections::hash_map::Entry::Vacant(e) = came_from.entry(nb) {
+                e.insert(current);
+                q.push_back(nb);
+                if let Some(trace) = trace.as_mut() {
+                    trace.last_mut().unwrap().queued.push(nb);
                 }
             }
         }
     }
     None
-}
\ No newline at end of file
+}
+
+// path from start to goal without the start itself, so its length is the number of steps
+pub fn bfs_grid<T>(
+    grid: &dyn GridView<T>,
+    is_valid_move: fn(&Point, &T, &Point, &T) -> bool,
+    start: Point,
+    goal: Point,
+) -> Option<Vec<Point>>
+    where
+        T: PartialEq + Copy,
+{
+    bfs(start, goal, |current| {
+        let current_val = grid.get_value(current).unwrap();
+        neighbors(current).filter(move |next| {
+            grid.get_value(*next).is_some_and(|next_val| is_valid_move(&current, &current_val, next, &next_val))
+        })
+    }).map(|path| path[1..].to_vec())
+}
+
+// bfs_grid that also returns every expansion, the trace starts with start
+pub fn bfs_grid_traced<T>(
+    grid: &dyn GridView<T>,
+    is_valid_move: fn(&Point, &T, &Point, &T) -> bool,
+    start: Point,
+    goal: Point,
+) -> (Option<Vec<Point>>, Vec<Expansion<Point>>)
+    where
+        T: PartialEq + Copy,
+{
+    let (path, trace) = bfs_traced(start, goal, |current| {
+        let current_val = grid.get_value(current).unwrap();
+        neighbors(current).filter(move |next| {
+            grid.get_value(*next).is_some_and(|next_val| is_valid_move(&current, &current_val, next, &next_val))
+        })
+    });
+    (path.map(|path| path[1..].to_vec()), trace)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dense_y_up() {
+        let parsed = GridParser::new().y_up().marker('S', '.').parse_dense("#S\n..\n.#", |c| c);
+        // the bottom line is row 0, going up is going to higher rows like NORTH = [0, 1]
+        assert_eq!(parsed.grid, vec![vec!['.', '#'], vec!['.', '.'], vec!['#', '.']]);
+        assert_eq!(parsed.marker('S'), Some(Point::new(1, 2)));
+        assert_eq!(parsed.grid.get_value(parsed.marker('S').unwrap() + Point::new(0, -2)), Some('#'));
+    }
+
+    #[test]
+    fn views() {
+        let grid = GridParser::new().parse_dense("30373\n25512\n65332\n33549\n35390", |c| c.to_digit(10).unwrap() as i8).grid;
+        assert_eq!(grid.neighbors4(Point::new(0, 0)).map(|(_, h)| h).collect::<Vec<_>>(), vec![2, 0]);
+        assert_eq!(grid.neighbors8(Point::new(4, 4)).count(), 3);
+        assert_eq!(grid.ray(Point::new(0, 0), Point::new(1, 1)).map(|(_, h)| h).collect::<Vec<_>>(), vec![5, 3, 4, 0]);
+        assert_eq!(grid.ray(Point::new(2, 2), Point::new(0, 0)).count(), 0);
+        assert_eq!(grid.row(1).map(|(_, h)| h).collect::<Vec<_>>(), vec![2, 5, 5, 1, 2]);
+        assert_eq!(grid.col(4).map(|(_, h)| h).collect::<Vec<_>>(), vec![3, 2, 2, 9, 0]);
+        let window = SubGrid::new(&grid, Point::new(1, 1), Point::new(3, 3));
+        assert_eq!(window.extents(), (Point::new(1, 1), Point::new(3, 3)));
+        assert_eq!(window.get_value(Point::new(0, 0)), None);
+        assert_eq!(window.row(2).map(|(_, h)| h).collect::<Vec<_>>(), vec![5, 3, 3]);
+        assert_eq!(window.neighbors4(Point::new(1, 1)).count(), 2);
+        assert_eq!(window.ray(Point::new(1, 1), Point::new(1, 0)).count(), 2);
+
+        let mut sparse = SparseGrid::new();
+        sparse.insert(Point::new(0, 0), 1);
+        assert_eq!(sparse.ray(Point::new(0, 0), Point::new(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn sparse_extents() {
+        let mut grid = (1..=4).flat_map(|x| (1..=3).map(move |y| (Point::new(x, y), '#'))).collect::<SparseGrid<_>>();
+        assert_eq!(grid.extents(), (Point::new(1, 1), Point::new(4, 3)));
+        for y in 1..=3 {
+            grid.remove(&Point::new(4, y));
+        }
+        assert_eq!(grid.extents(), (Point::new(1, 1), Point::new(3, 3)));
+        grid.insert(Point::new(-3, 20), '#');
+        assert_eq!(grid.extents(), (Point::new(-3, 1), Point::new(3, 20)));
+        // an insert right after a border cell went must not grow the stale box
+        grid.remove(&Point::new(-3, 20));
+        grid.insert(Point::new(2, 2), '.');
+        assert_eq!(grid.extents(), (Point::new(1, 1), Point::new(3, 3)));
+        grid.remove(&Point::new(3, 3));
+        grid.insert(Point::new(3, 3), '#');
+        assert_eq!(grid.extents(), (Point::new(1, 1), Point::new(3, 3)));
+        grid.insert(Point::new(5, 0), '#');
+        assert_eq!(grid.extents(), (Point::new(1, 0), Point::new(5, 3)));
+        grid.clear();
+        assert_eq!(grid.extents(), (Point::new(0, 0), Point::new(0, 0)));
+        grid.insert(Point::new(7, -7), '#');
+        assert_eq!(grid.extents(), (Point::new(7, -7), Point::new(7, -7)));
+    }
+
+    #[test]
+    fn sparse_flips() {
+        let mut grid = SparseGrid::from(HashMap::from([(Point::new(1, 0), 'a'), (Point::new(3, 0), 'b'), (Point::new(1, 5), 'c')]));
+        grid.flip_horizontal();
+        assert_eq!(*grid, HashMap::from([(Point::new(3, 0), 'a'), (Point::new(1, 0), 'b'), (Point::new(3, 5), 'c')]));
+        assert_eq!(grid.extents(), (Point::new(1, 0), Point::new(3, 5)));
+        grid.flip_vertical();
+        assert_eq!(*grid, HashMap::from([(Point::new(3, 5), 'a'), (Point::new(1, 5), 'b'), (Point::new(3, 0), 'c')]));
+        assert_eq!(grid.extents(), (Point::new(1, 0), Point::new(3, 5)));
+        grid.transpose();
+        assert_eq!(*grid, HashMap::from([(Point::new(5, 3), 'a'), (Point::new(5, 1), 'b'), (Point::new(0, 3), 'c')]));
+        assert_eq!(grid.extents(), (Point::new(0, 1), Point::new(5, 3)));
+    }
+
+    #[test]
+    fn parse_dense_origin() {
+        // wherever the origin puts the text, the dense grid and its markers start at [0, 0]
+        for parser in [GridParser::new(), GridParser::new().y_up()] {
+            let parser = parser.marker('S', '.');
+            let plain = parser.clone().parse_dense("S.\n.#", |c| c);
+            for origin in [Point::new(-1, 0), Point::new(1, 0), Point::new(5, -3)] {
+                assert_eq!(parser.clone().origin(origin).parse_dense("S.\n.#", |c| c), plain, "{:?}", origin);
+            }
+        }
+        // a skipped first column or bottom line is still part of the grid
+        let skipped = GridParser::new().origin(Point::new(2, 2)).skip(' ').parse_dense(" a\n b", |c| c);
+        assert_eq!(skipped.grid, vec![vec!['\0', 'a'], vec!['\0', 'b']]);
+        let skipped = GridParser::new().y_up().skip(' ').parse_dense("ab\n  ", |c| c);
+        assert_eq!(skipped.grid, vec![vec!['\0', '\0'], vec!['a', 'b']]);
+    }
+}
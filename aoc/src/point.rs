@@ -0,0 +1,343 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+// Integer 2d point, y points up like the direction constants in the crate root.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    pub fn dot(self, other: Point) -> i64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn signum(self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(self, other: Point) -> i64 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    // rotations around the origin, with y up NORTH.rot90_cw() is EAST
+    pub fn rot90_cw(self) -> Point {
+        Point::new(self.y, -self.x)
+    }
+
+    pub fn rot90_ccw(self) -> Point {
+        Point::new(-self.y, self.x)
+    }
+
+    pub fn rot180(self) -> Point {
+        -self
+    }
+
+    pub fn neighbors4(self) -> impl Iterator<Item = Point> {
+        crate::DIRECTIONS.into_iter().map(move |d| self + d)
+    }
+
+    pub fn neighbors8(self) -> impl Iterator<Item = Point> {
+        crate::DIRECTIONS_INCL_DIAGONALS.into_iter().map(move |d| self + d)
+    }
+}
+
+impl From<[i64; 2]> for Point {
+    fn from([x, y]: [i64; 2]) -> Point {
+        Point::new(x, y)
+    }
+}
+
+impl From<Point> for [i64; 2] {
+    fn from(p: Point) -> [i64; 2] {
+        [p.x, p.y]
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from((x, y): (i64, i64)) -> Point {
+        Point::new(x, y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Index<usize> for Point {
+    type Output = i64;
+
+    fn index(&self, i: usize) -> &i64 {
+        match i {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("point index out of range: {}", i),
+        }
+    }
+}
+
+impl IndexMut<usize> for Point {
+    fn index_mut(&mut self, i: usize) -> &mut i64 {
+        match i {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("point index out of range: {}", i),
+        }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, n: i64) -> Point {
+        Point::new(self.x * n, self.y * n)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Vec3 {
+    pub const fn new(x: i64, y: i64, z: i64) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+
+    pub fn dot(self, other: Vec3) -> i64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn signum(self) -> Vec3 {
+        Vec3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    pub fn manhattan(self, other: Vec3) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+}
+
+impl From<[i64; 3]> for Vec3 {
+    fn from([x, y, z]: [i64; 3]) -> Vec3 {
+        Vec3::new(x, y, z)
+    }
+}
+
+impl From<Vec3> for [i64; 3] {
+    fn from(v: Vec3) -> [i64; 3] {
+        [v.x, v.y, v.z]
+    }
+}
+
+impl fmt::Display for Vec3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl Index<usize> for Vec3 {
+    type Output = i64;
+
+    fn index(&self, i: usize) -> &i64 {
+        match i {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("vector index out of range: {}", i),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vec3 {
+    fn index_mut(&mut self, i: usize) -> &mut i64 {
+        match i {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("vector index out of range: {}", i),
+        }
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<i64> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, n: i64) -> Vec3 {
+        Vec3::new(self.x * n, self.y * n, self.z * n)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, other: Vec3) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vec3 {
+    fn sub_assign(&mut self, other: Vec3) {
+        *self = *self - other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DIRECTIONS_INCL_DIAGONALS, EAST, NORTH, SOUTH, WEST};
+
+    #[test]
+    fn point_arithmetic() {
+        let (a, b) = (Point::new(3, -2), Point::new(-1, 5));
+        assert_eq!(a + b, Point::new(2, 3));
+        assert_eq!(a - b, Point::new(4, -7));
+        assert_eq!(a * 3, Point::new(9, -6));
+        assert_eq!(-a, Point::new(-3, 2));
+        let mut c = a;
+        c += b;
+        assert_eq!(c, a + b);
+        c -= b;
+        assert_eq!(c, a);
+        assert_eq!((a.dot(b), a.signum()), (-13, Point::new(1, -1)));
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (11, 7));
+        assert_eq!(a.to_string(), "(3, -2)");
+    }
+
+    #[test]
+    fn point_rotations() {
+        assert_eq!([NORTH, EAST, SOUTH, WEST].map(Point::rot90_cw), [EAST, SOUTH, WEST, NORTH]);
+        assert_eq!([NORTH, EAST, SOUTH, WEST].map(Point::rot90_ccw), [WEST, NORTH, EAST, SOUTH]);
+        assert_eq!([NORTH, EAST].map(Point::rot180), [SOUTH, WEST]);
+        for p in DIRECTIONS_INCL_DIAGONALS.into_iter().chain([Point::new(3, -7), Point::new(0, 0)]) {
+            assert_eq!(p.rot90_cw().rot90_ccw(), p);
+            assert_eq!(p.rot90_ccw().rot90_cw(), p);
+            assert_eq!(p.rot90_cw().rot90_cw(), p.rot180());
+            assert_eq!(p.rot180().rot180(), p);
+        }
+    }
+
+    #[test]
+    fn point_conversions() {
+        let p = Point::new(4, -9);
+        assert_eq!(Point::from([4, -9]), p);
+        assert_eq!(Point::from((4, -9)), p);
+        assert_eq!(<[i64; 2]>::from(p), [4, -9]);
+        let mut q = p;
+        q[0] += 1;
+        q[1] = 2;
+        assert_eq!((q, q[0], q[1]), (Point::new(5, 2), 5, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "point index out of range: 2")]
+    fn point_index_out_of_range() {
+        let _ = Point::new(0, 0)[2];
+    }
+
+    #[test]
+    fn vec3_arithmetic() {
+        let (a, b) = (Vec3::new(1, -2, 3), Vec3::new(4, 0, -1));
+        assert_eq!(a + b, Vec3::new(5, -2, 2));
+        assert_eq!(a - b, Vec3::new(-3, -2, 4));
+        assert_eq!(a * -2, Vec3::new(-2, 4, -6));
+        assert_eq!(-a, Vec3::new(-1, 2, -3));
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        assert_eq!((a.dot(b), a.manhattan(b), a.signum()), (1, 9, Vec3::new(1, -1, 1)));
+        assert_eq!(Vec3::new(1, 0, 0).cross(Vec3::new(0, 1, 0)), Vec3::new(0, 0, 1));
+        assert_eq!(a.cross(b), -b.cross(a));
+        assert_eq!(a.to_string(), "(1, -2, 3)");
+    }
+
+    #[test]
+    fn vec3_conversions() {
+        let v = Vec3::from([7, 8, -9]);
+        assert_eq!(v, Vec3::new(7, 8, -9));
+        assert_eq!(<[i64; 3]>::from(v), [7, 8, -9]);
+        let mut w = v;
+        w[2] = 0;
+        w[0] -= 7;
+        assert_eq!((w, w[1]), (Vec3::new(0, 8, 0), 8));
+    }
+}
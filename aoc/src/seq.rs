@@ -0,0 +1,158 @@
+// Sqrt-decomposed list over the ids 0..n. Every id knows the block it lives in, so
+// finding its position, removing and re-inserting it all cost O(sqrt(n)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqrtList {
+    blocks: Vec<Vec<usize>>,
+    block_of: Vec<usize>,
+    block_size: usize,
+    len: usize,
+}
+
+impl SqrtList {
+    pub fn new(n: usize) -> SqrtList {
+        let mut list = SqrtList {
+            blocks: vec![(0..n).collect()],
+            block_of: vec![0; n],
+            block_size: ((n as f64).sqrt() as usize).max(1),
+            len: n,
+        };
+        list.rebuild();
+        list
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn rebuild(&mut self) {
+        let all = self.iter().collect::<Vec<_>>();
+        self.blocks = all.chunks(self.block_size).map(|c| c.to_vec()).collect();
+        if self.blocks.is_empty() {
+            self.blocks.push(vec![]);
+        }
+        for (b, block) in self.blocks.iter().enumerate() {
+            for &id in block {
+                self.block_of[id] = b;
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().flatten().copied()
+    }
+
+    pub fn position(&self, id: usize) -> usize {
+        let b = self.block_of[id];
+        let before = self.blocks[..b].iter().map(|block| block.len()).sum::<usize>();
+        before + self.blocks[b].iter().position(|&i| i == id).unwrap()
+    }
+
+    // block index and offset inside it for a position, pos == len points past the end
+    fn locate(&self, mut pos: usize) -> (usize, usize) {
+        for (b, block) in self.blocks.iter().enumerate() {
+            if pos < block.len() {
+                return (b, pos);
+            }
+            pos -= block.len();
+        }
+        let last = self.blocks.len() - 1;
+        (last, self.blocks[last].len() + pos)
+    }
+
+    pub fn get(&self, pos: usize) -> usize {
+        let (b, i) = self.locate(pos);
+        self.blocks[b][i]
+    }
+
+    pub fn remove(&mut self, pos: usize) -> usize {
+        let (b, i) = self.locate(pos);
+        self.len -= 1;
+        self.blocks[b].remove(i)
+    }
+
+    pub fn insert(&mut self, pos: usize, id: usize) {
+        let (b, i) = self.locate(pos);
+        self.blocks[b].insert(i, id);
+        self.block_of[id] = b;
+        self.len += 1;
+        if self.blocks[b].len() > 2 * self.block_size {
+            self.rebuild();
+        }
+    }
+
+    // move id by k places, treating the list as a ring it has been taken out of
+    pub fn move_by(&mut self, id: usize, k: i64) {
+        if self.len < 2 {
+            return;
+        }
+        let p = self.position(id);
+        self.remove(p);
+        let new_pos = (p as i64 + k).rem_euclid(self.len as i64);
+        self.insert(new_pos as usize, id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every id finds itself and no block grew past twice the block size
+    fn check(list: &SqrtList) {
+        for (pos, id) in list.iter().enumerate() {
+            assert_eq!(list.position(id), pos);
+            assert_eq!(list.get(pos), id);
+        }
+        assert!(list.blocks.iter().all(|b| b.len() <= 2 * list.block_size));
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut list = SqrtList::new(5);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(list.remove(2), 2);
+        assert_eq!((list.len(), list.position(3)), (4, 2));
+        list.insert(0, 2);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![2, 0, 1, 3, 4]);
+        assert_eq!(list.remove(4), 4);
+        list.insert(4, 4);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![2, 0, 1, 3, 4]);
+        check(&list);
+        assert!(SqrtList::new(0).is_empty());
+    }
+
+    #[test]
+    fn move_by() {
+        let mut list = SqrtList::new(5);
+        let moves = [
+            (1, 2, [0, 2, 3, 1, 4]),
+            (4, 1, [0, 4, 2, 3, 1]),
+            (0, -1, [4, 2, 3, 0, 1]),
+            (2, 9, [4, 3, 2, 0, 1]),
+            (3, -7, [4, 2, 3, 0, 1]),
+        ];
+        for (id, k, expected) in moves {
+            list.move_by(id, k);
+            assert_eq!(list.iter().collect::<Vec<_>>(), expected, "{} by {}", id, k);
+            check(&list);
+        }
+        let mut single = SqrtList::new(1);
+        single.move_by(0, 5);
+        assert_eq!(single.iter().collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn move_by_rebuilds() {
+        // blocks of 3, the fourth id moved to the front overfills the first block
+        let mut list = SqrtList::new(9);
+        for id in [8, 7, 6, 5] {
+            list.move_by(id, -(list.position(id) as i64));
+            check(&list);
+        }
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![5, 6, 7, 8, 0, 1, 2, 3, 4]);
+        assert_eq!(list.blocks, vec![vec![5, 6, 7], vec![8, 0, 1], vec![2, 3, 4]]);
+    }
+}
@@ -0,0 +1,98 @@
+// Discrete time simulations. A day only says how to advance one step, the drivers below do the
+// looping, so running, debugging and rendering work the same for all of them.
+use std::hash::Hash;
+
+use crate::cycle::{self, Detector};
+pub use crate::cycle::Cycle;
+
+pub trait Simulation {
+    // what a renderer or a test looks at, often the simulation itself
+    type State: ?Sized;
+    // enough to go back to an earlier step
+    type Snapshot: Clone;
+
+    fn step(&mut self);
+    fn state(&self) -> &Self::State;
+    fn snapshot(&self) -> Self::Snapshot;
+    fn restore(&mut self, snapshot: &Self::Snapshot);
+
+    fn is_done(&self) -> bool {
+        false
+    }
+}
+
+// at most n steps, fewer if the simulation is done before, returns how many were taken
+pub fn run<S: Simulation + ?Sized>(sim: &mut S, n: usize) -> usize {
+    let mut steps = 0;
+    while steps < n && !sim.is_done() {
+        sim.step();
+        steps += 1;
+    }
+    steps
+}
+
+pub fn run_to_end<S: Simulation + ?Sized>(sim: &mut S) -> usize {
+    run(sim, usize::MAX)
+}
+
+// Steps until one doesn't change the key any more and returns the number of steps including that
+// one. None if the simulation is done or the limit is reached first.
+pub fn run_until_fixed_point<S: Simulation, K: PartialEq>(sim: &mut S, limit: Option<usize>, key: impl Fn(&S) -> K) -> Option<usize> {
+    let mut previous = key(sim);
+    let mut steps = 0;
+    while limit.map(|l| steps < l).unwrap_or(true) && !sim.is_done() {
+        sim.step();
+        steps += 1;
+        let current = key(sim);
+        if current == previous {
+            return Some(steps);
+        }
+        previous = current;
+    }
+    None
+}
+
+// Steps until a key shows up a second time. The simulation is left at step start + period,
+// the state it had at step start again as far as the key can tell.
+pub fn find_cycle<S: Simulation, K: Hash + Eq>(sim: &mut S, limit: Option<usize>, key: impl Fn(&S) -> K) -> Option<Cycle> {
+    let mut detector = Detector::new();
+    let mut steps = 0;
+    loop {
+        if let Some(cycle) = detector.push(key(sim)) {
+            return Some(cycle);
+        }
+        if limit.map(|l| steps >= l).unwrap_or(false) || sim.is_done() {
+            return None;
+        }
+        sim.step();
+        steps += 1;
+    }
+}
+
+// The measure after n steps, once the key repeats the rest is extrapolated from one period.
+// Only works for measures that grow by the same amount every period, like a height or a count.
+pub fn run_extrapolated<S: Simulation, K: Hash + Eq>(sim: &mut S, n: usize, key: impl Fn(&S) -> K, measure: impl Fn(&S) -> i64) -> i64 {
+    let mut detector = Detector::new();
+    let mut measures = vec![];
+    for steps in 0..=n {
+        measures.push(measure(sim));
+        if let Some(cycle) = detector.push(key(sim)) {
+            return cycle::project(cycle, &measures, n);
+        }
+        if steps == n || sim.is_done() {
+            break;
+        }
+        sim.step();
+    }
+    measures[measures.len() - 1]
+}
+
+// snapshots of the start and after every step, at most n steps
+pub fn record<S: Simulation + ?Sized>(sim: &mut S, n: usize) -> Vec<S::Snapshot> {
+    let mut history = vec![sim.snapshot()];
+    while history.len() <= n && !sim.is_done() {
+        sim.step();
+        history.push(sim.snapshot());
+    }
+    history
+}
@@ -0,0 +1,103 @@
+// What a search did, one expansion at a time, and a simulation that plays it back so a search
+// can be stepped through like any other simulation.
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::sim::Simulation;
+
+// a node taken off the frontier and the neighbours that were queued because of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expansion<N> {
+    pub node: N,
+    pub queued: Vec<N>,
+}
+
+impl<N> Expansion<N> {
+    pub fn new(node: N) -> Expansion<N> {
+        Expansion { node, queued: vec![] }
+    }
+}
+
+// one expansion per step, the path shows up once all of them are played
+#[derive(Debug, Clone)]
+pub struct Replay<N> {
+    trace: Vec<Expansion<N>>,
+    path: Option<Vec<N>>,
+    steps: usize,
+    visited: HashSet<N>,
+    frontier: HashSet<N>,
+}
+
+impl<N: Copy + Eq + Hash> Replay<N> {
+    pub fn new(trace: Vec<Expansion<N>>, path: Option<Vec<N>>) -> Replay<N> {
+        let frontier = trace.first().map(|e| e.node).into_iter().collect();
+        Replay { trace, path, steps: 0, visited: HashSet::new(), frontier }
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn len(&self) -> usize {
+        self.trace.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trace.is_empty()
+    }
+
+    // the node expanded by the last step
+    pub fn current(&self) -> Option<N> {
+        self.steps.checked_sub(1).map(|i| self.trace[i].node)
+    }
+
+    pub fn visited(&self) -> &HashSet<N> {
+        &self.visited
+    }
+
+    pub fn frontier(&self) -> &HashSet<N> {
+        &self.frontier
+    }
+
+    pub fn path(&self) -> Option<&[N]> {
+        if self.is_done() {
+            self.path.as_deref()
+        } else {
+            None
+        }
+    }
+}
+
+impl<N: Copy + Eq + Hash> Simulation for Replay<N> {
+    type State = Self;
+    type Snapshot = usize;
+
+    fn step(&mut self) {
+        if let Some(expansion) = self.trace.get(self.steps) {
+            self.frontier.remove(&expansion.node);
+            self.visited.insert(expansion.node);
+            self.frontier.extend(expansion.queued.iter().copied());
+            self.steps += 1;
+        }
+    }
+
+    fn state(&self) -> &Self {
+        self
+    }
+
+    fn snapshot(&self) -> usize {
+        self.steps
+    }
+
+    fn restore(&mut self, steps: &usize) {
+        let trace = std::mem::take(&mut self.trace);
+        *self = Replay::new(trace, self.path.take());
+        while self.steps < *steps && !self.is_done() {
+            self.step();
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.steps >= self.trace.len()
+    }
+}
@@ -0,0 +1,105 @@
+// Parse and both parts of every registered day, each on its own, with variants benched next to
+// the part they replace. A day's real input is used when it is in input/2022, generated inputs of a few
+// sizes are benched either way so scaling shows up.
+//
+//   cargo bench --bench days -- day16                  only one day
+//   cargo bench --bench days -- --save-baseline main   remember the current numbers
+//   cargo bench --bench days -- --baseline main        compare against them
+//   cargo bench --bench days --features count-alloc    also print the allocations of one run
+use std::fs;
+
+use aoc_2022_rust::alloc_count::{self, Scope};
+use aoc_2022_rust::inputgen;
+use aoc_2022_rust::registry;
+use aoc_2022_rust::solution::{input_path, Entry};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn count_alloc<T>(name: &str, f: impl FnOnce() -> T) {
+    let scope = Scope::start();
+    black_box(f());
+    if let Some(a) = scope.finish() {
+        println!("{:<40} {:>10} allocations {:>12} bytes {:>12} peak", name, a.allocations, a.bytes, a.peak);
+    }
+}
+
+// sizes are what inputgen::generate takes, chosen so the largest runs in well under a second
+fn sizes(day: u32) -> &'static [usize] {
+    match day {
+        1 => &[100, 1000],
+        2 => &[100, 2500],
+        3 => &[30, 300],
+        4 => &[100, 1000],
+        5 => &[50, 500],
+        6 => &[100, 4000],
+        7 => &[20, 200],
+        8 => &[20, 100],
+        9 => &[200, 2000],
+        10 => &[1],
+        11 => &[4, 8],
+        12 => &[20, 80],
+        13 => &[20, 150],
+        14 => &[20, 150],
+        15 => &[1, 9],
+        16 => &[6, 12],
+        17 => &[40, 10000],
+        19 => &[3, 10],
+        20 => &[500, 5000],
+        21 => &[100, 2000],
+        22 => &[4, 50],
+        23 => &[20, 70],
+        24 => &[6, 25],
+        25 => &[20, 200],
+        _ => &[10],
+    }
+}
+
+// all entries of one day, a part whose answer is an error is left out with a note
+fn bench_day(c: &mut Criterion, entries: &[Entry]) {
+    let day = entries[0].day;
+    let mut inputs = fs::read_to_string(input_path(day)).ok()
+        .map(|input| ("input".to_string(), input))
+        .into_iter()
+        .collect::<Vec<_>>();
+    inputs.extend(sizes(day).iter().filter_map(|&size| inputgen::generate(day, 1, size).map(|input| (size.to_string(), input))));
+    if inputs.is_empty() {
+        println!("day{:02}: no puzzle input and no generator, not benched", day);
+        return;
+    }
+
+    let mut group = c.benchmark_group(format!("day{:02}", day));
+    group.sample_size(10);
+    for (label, input) in &inputs {
+        // all parts share the parser, the first one stands in for it
+        group.bench_with_input(BenchmarkId::new("parse", label), input, |b, input| b.iter(|| entries[0].prepare(black_box(input))));
+        if alloc_count::enabled() {
+            count_alloc(&format!("day{:02}/parse/{}", day, label), || entries[0].prepare(input));
+        }
+        for entry in entries {
+            let part = match entry.prepare(input).map(|part| (part(), part)) {
+                Ok((Ok(_), part)) => part,
+                Ok((Err(e), _)) | Err(e) => {
+                    println!("{} on {}: {}, not benched", entry.name(), label, e);
+                    continue;
+                }
+            };
+            let id = match entry.variant {
+                Some(v) => format!("part{}/{}", entry.part, v),
+                None => format!("part{}", entry.part),
+            };
+            group.bench_function(BenchmarkId::new(&id, label), |b| b.iter(&part));
+            if alloc_count::enabled() {
+                count_alloc(&format!("day{:02}/{}/{}", day, id, label), &part);
+            }
+        }
+    }
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    for entries in registry().chunk_by(|a, b| a.day == b.day) {
+        bench_day(c, entries);
+    }
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+// The aoc grid helpers on synthetic grids of a few sizes, see benches/days.rs for baselines.
+use aoc::*;
+use aoc_2022_rust::inputgen;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::Rng;
+
+const SIZES: [usize; 3] = [32, 128, 512];
+
+// digits 1-9 as move costs, about a fifth of the cells are walls marked with 0
+fn cost_grid(size: usize) -> Vec<Vec<u8>> {
+    let mut rng = inputgen::rng(size as u64);
+    let mut grid = (0..size)
+        .map(|_| (0..size).map(|_| if rng.gen_ratio(1, 5) { 0 } else { rng.gen_range(1..10) }).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    grid[0][0] = 1;
+    grid[size - 1][size - 1] = 1;
+    grid
+}
+
+fn cost(_p1: &Point, _c1: &u8, _p2: &Point, c2: &u8) -> Option<i64> {
+    (*c2 != 0).then_some(*c2 as i64)
+}
+
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    group.sample_size(10);
+    for size in SIZES {
+        let grid = cost_grid(size);
+        let goal = Point::new(size as i64 - 1, size as i64 - 1);
+        group.bench_with_input(BenchmarkId::new("astar_grid", size), &grid, |b, grid| {
+            b.iter(|| astar_grid(black_box(grid), |_, _| true, cost, Point::new(0, 0), goal))
+        });
+        group.bench_with_input(BenchmarkId::new("dijkstra_grid", size), &grid, |b, grid| {
+            b.iter(|| dijkstra_grid(black_box(grid), |_, _| true, cost, Point::new(0, 0), goal))
+        });
+        group.bench_with_input(BenchmarkId::new("bfs_grid", size), &grid, |b, grid| {
+            b.iter(|| bfs_grid(black_box(grid), |_, _, _, c2| *c2 != 0, Point::new(0, 0), goal))
+        });
+    }
+    group.finish();
+}
+
+fn transform(c: &mut Criterion) {
+    let mut group = c.benchmark_group("grid");
+    for size in SIZES {
+        let grid = cost_grid(size);
+        group.bench_with_input(BenchmarkId::new("transpose", size), &grid, |b, grid| {
+            b.iter_batched_ref(|| grid.clone(), |grid| grid.transpose(), BatchSize::LargeInput)
+        });
+        let text = grid.iter()
+            .map(|row| row.iter().map(|&c| if c == 0 { '#' } else { '.' }).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        group.bench_with_input(BenchmarkId::new("parse_sparse", size), &text, |b, text| {
+            b.iter(|| GridParser::new().parse_sparse(black_box(text), |c| (c == '#').then_some(())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, search, transform);
+criterion_main!(benches);
@@ -0,0 +1,145 @@
+// Allocation counting for the runner and the benches. The allocator only becomes the global one
+// with the count-alloc feature, without it every Scope finishes with None.
+//
+//   cargo run --release --features count-alloc -- --format markdown
+//
+// The counters are shared by all threads, like the peak memory they only mean something while a
+// single solution runs at a time.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicU64 = AtomicU64::new(0);
+static PEAK: AtomicU64 = AtomicU64::new(0);
+
+pub struct Counting;
+
+impl Counting {
+    fn allocated(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size as u64, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn freed(size: usize) {
+        LIVE.fetch_sub(size as u64, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc(layout);
+        if !p.is_null() {
+            Counting::allocated(layout.size());
+        }
+        p
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc_zeroed(layout);
+        if !p.is_null() {
+            Counting::allocated(layout.size());
+        }
+        p
+    }
+
+    unsafe fn dealloc(&self, p: *mut u8, layout: Layout) {
+        System.dealloc(p, layout);
+        Counting::freed(layout.size());
+    }
+
+    // a realloc counts as one more allocation of the new size
+    unsafe fn realloc(&self, p: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let q = System.realloc(p, layout, new_size);
+        if !q.is_null() {
+            Counting::freed(layout.size());
+            Counting::allocated(new_size);
+        }
+        q
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub allocations: u64,
+    pub bytes: u64,
+    // most heap bytes live at once on top of what was live when the scope started
+    pub peak: u64,
+}
+
+impl Stats {
+    // two scopes after each other
+    pub fn then(self, other: Stats) -> Stats {
+        Stats {
+            allocations: self.allocations + other.allocations,
+            bytes: self.bytes + other.bytes,
+            peak: self.peak.max(other.peak),
+        }
+    }
+}
+
+pub fn enabled() -> bool {
+    cfg!(feature = "count-alloc")
+}
+
+pub struct Scope {
+    allocations: u64,
+    bytes: u64,
+    live: u64,
+}
+
+impl Scope {
+    // resets the peak, an enclosing scope keeps its own peak as long as it is the larger one
+    pub fn start() -> Scope {
+        let live = LIVE.load(Ordering::Relaxed);
+        PEAK.store(live, Ordering::Relaxed);
+        Scope {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+            live,
+        }
+    }
+
+    pub fn finish(self) -> Option<Stats> {
+        if !enabled() {
+            return None;
+        }
+        Some(Stats {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - self.allocations,
+            bytes: BYTES.load(Ordering::Relaxed) - self.bytes,
+            peak: PEAK.load(Ordering::Relaxed).saturating_sub(self.live),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hint::black_box;
+
+    #[test]
+    pub fn test_scope() {
+        let scope = Scope::start();
+        let v = black_box(vec![0u64; 1000]);
+        drop(v);
+        let stats = scope.finish();
+        if enabled() {
+            // other tests allocate at the same time, so only lower bounds hold
+            let stats = stats.unwrap();
+            assert!(stats.allocations >= 1);
+            assert!(stats.bytes >= 8000);
+            assert!(stats.peak >= 8000);
+        } else {
+            assert_eq!(stats, None);
+        }
+    }
+
+    #[test]
+    pub fn test_then() {
+        let a = Stats { allocations: 2, bytes: 100, peak: 80 };
+        let b = Stats { allocations: 3, bytes: 50, peak: 50 };
+        assert_eq!(a.then(b), Stats { allocations: 5, bytes: 150, peak: 80 });
+    }
+}
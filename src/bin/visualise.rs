@@ -0,0 +1,491 @@
+extern crate aoc_2022_rust;
+
+// Steps through a day's simulation or search in the terminal, no window system needed, so it
+// also works over ssh. Build with --features tui.
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::fs;
+use std::io;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use aoc::sim::Simulation;
+use aoc::*;
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Paragraph, Widget};
+use ratatui::DefaultTerminal;
+
+use aoc_2022_rust::solution::input_path;
+use aoc_2022_rust::{day09, day10, day11, day12, day14, day17, day20, day23, day24, inputgen, registry};
+
+const USAGE: &str = "usage: visualise [--input FILE] [--seed N] [--size N] [--bfs] DAY [PART]
+days: 9 10 11 12 14 17 20 23 24";
+
+const HELP: &str = "space play/pause  n step  b back  r restart  [ ] speed  arrows/hjkl scroll  \
+                    pgup/pgdn page  home top  + - zoom  q quit";
+
+const TICK: Duration = Duration::from_millis(50);
+const MAX_ZOOM: i64 = 8;
+const MAX_SPEED: usize = 4096;
+const HISTORY: usize = 10_000;
+const CHECKPOINT_EVERY: usize = 100;
+
+// highlights, when zoomed out the highest one in a block wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Mark {
+    Wall,
+    Item,
+    Visited,
+    Frontier,
+    Active,
+    Path,
+}
+
+impl Mark {
+    fn style(&self) -> Style {
+        match self {
+            Mark::Wall => Style::new().fg(Color::DarkGray),
+            Mark::Item => Style::new().fg(Color::Yellow),
+            Mark::Visited => Style::new().fg(Color::Blue),
+            Mark::Frontier => Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            Mark::Active => Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+            Mark::Path => Style::new().fg(Color::Black).bg(Color::Red).add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+// what one step looks like, rows top down
+struct Frame {
+    cells: Vec<Vec<char>>,
+    marks: HashMap<Point, Mark>,
+}
+
+impl Frame {
+    // lines are padded so the cells are a proper grid
+    fn text(text: &str, mark: impl Fn(char) -> Option<Mark>) -> Frame {
+        let lines = text.lines().map(|l| l.chars().collect::<Vec<_>>()).collect::<Vec<_>>();
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let mut marks = HashMap::new();
+        let cells = lines.into_iter().enumerate().map(|(y, mut line)| {
+            line.resize(width, ' ');
+            for (x, c) in line.iter().enumerate() {
+                if let Some(m) = mark(*c) {
+                    marks.insert(Point::new(x as i64, y as i64), m);
+                }
+            }
+            line
+        }).collect();
+        Frame { cells, marks }
+    }
+
+    // the most interesting cell of a zoom x zoom block
+    fn block(&self, corner: Point, zoom: i64) -> Option<(char, Option<Mark>)> {
+        let rank = |c: char, m: Option<Mark>| (m, !matches!(c, ' ' | '.'));
+        let mut best: Option<(char, Option<Mark>)> = None;
+        for dy in 0..zoom {
+            for dx in 0..zoom {
+                let p = corner + Point::new(dx, dy);
+                if let Some(c) = self.cells.get_value(p) {
+                    let m = self.marks.get(&p).copied();
+                    if best.map(|(bc, bm)| rank(c, m) > rank(bc, bm)).unwrap_or(true) {
+                        best = Some((c, m));
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+trait View {
+    fn step(&mut self);
+    fn back(&mut self);
+    fn restart(&mut self);
+    fn is_done(&self) -> bool;
+    fn steps(&self) -> usize;
+    fn frame(&self) -> Frame;
+    fn status(&self) -> String;
+}
+
+// any simulation, a snapshot is kept every few steps and going back replays forward from the
+// nearest one, only the last HISTORY steps can be stepped back through
+struct Stepper<S: Simulation> {
+    sim: S,
+    steps: usize,
+    start: S::Snapshot,
+    checkpoints: VecDeque<(usize, S::Snapshot)>,
+    draw: Box<dyn Fn(&S) -> Frame>,
+    describe: Box<dyn Fn(&S) -> String>,
+}
+
+impl<S: Simulation> Stepper<S> {
+    fn new(sim: S, draw: impl Fn(&S) -> Frame + 'static, describe: impl Fn(&S) -> String + 'static) -> Stepper<S> {
+        let start = sim.snapshot();
+        let checkpoints = VecDeque::from([(0, start.clone())]);
+        Stepper { sim, steps: 0, start, checkpoints, draw: Box::new(draw), describe: Box::new(describe) }
+    }
+}
+
+impl<S: Simulation> View for Stepper<S> {
+    fn step(&mut self) {
+        if !self.sim.is_done() {
+            self.sim.step();
+            self.steps += 1;
+            if self.steps.is_multiple_of(CHECKPOINT_EVERY) {
+                self.checkpoints.push_back((self.steps, self.sim.snapshot()));
+                if self.checkpoints.len() > HISTORY / CHECKPOINT_EVERY {
+                    self.checkpoints.pop_front();
+                }
+            }
+        }
+    }
+
+    fn back(&mut self) {
+        let Some(target) = self.steps.checked_sub(1) else { return };
+        let kept = self.checkpoints.partition_point(|&(steps, _)| steps <= target);
+        if kept == 0 {
+            return;
+        }
+        self.checkpoints.truncate(kept);
+        let (steps, snapshot) = &self.checkpoints[kept - 1];
+        self.sim.restore(snapshot);
+        self.steps = *steps;
+        while self.steps < target {
+            self.sim.step();
+            self.steps += 1;
+        }
+    }
+
+    fn restart(&mut self) {
+        self.sim.restore(&self.start);
+        self.steps = 0;
+        self.checkpoints = VecDeque::from([(0, self.start.clone())]);
+    }
+
+    fn is_done(&self) -> bool {
+        self.sim.is_done()
+    }
+
+    fn steps(&self) -> usize {
+        self.steps
+    }
+
+    fn frame(&self) -> Frame {
+        (self.draw)(&self.sim)
+    }
+
+    fn status(&self) -> String {
+        (self.describe)(&self.sim)
+    }
+}
+
+// the parsed input has to outlive the simulations borrowing it, which is the whole run
+fn leak<T>(data: T) -> &'static T {
+    Box::leak(Box::new(data))
+}
+
+fn view(day: u32, part: u8, bfs: bool, input: &str) -> Option<Box<dyn View>> {
+    let view: Box<dyn View> = match day {
+        9 => {
+            let knots = if part == 1 { 2 } else { 10 };
+            let rope = day09::Rope::<2>::new(knots, day09::Follow::Chebyshev);
+            Box::new(Stepper::new(day09::Motions::new(rope, leak(day09::generator(input))), draw_rope, |m| {
+                let rope = m.state();
+                format!("tail visited {}", rope.visited(rope.knots().len() - 1).len())
+            }))
+        }
+        10 => Box::new(Stepper::new(day10::Cpu::new(leak(day10::generator(input))), draw_crt, |cpu| {
+            format!("cycle {}  x {}  signal strength {}", cpu.next_cycle(), cpu.x(), cpu.signal_strength())
+        })),
+        11 => Box::new(Stepper::new(day11::Troop::new(&day11::generator(input), part == 1), |troop| {
+            let text = troop.state().iter().enumerate().map(|(i, m)| format!("Monkey {}: {}", i, m)).collect::<Vec<_>>();
+            Frame::text(&text.join("\n"), |_| None)
+        }, |troop| format!("round {}  monkey business {}", troop.round(), troop.monkey_business()))),
+        12 => {
+            let data = day12::generator(input);
+            let replay = day12::replay(&data, bfs);
+            Box::new(Stepper::new(replay, move |r| draw_search(&data, r), |r| {
+                let found = r.path().map(|p| format!("  path of {} nodes", p.len())).unwrap_or_default();
+                format!("expanded {} of {}  frontier {}{}", r.steps(), r.len(), r.frontier().len(), found)
+            }))
+        }
+        14 => {
+            let floor = if part == 1 { None } else { Some(2) };
+            let config = day14::CaveConfig { floor, ..Default::default() };
+            Box::new(Stepper::new(day14::Cave::new(&day14::generator(input), &config), |cave| {
+                Frame::text(&cave.to_string(), |c| match c {
+                    '#' => Some(Mark::Wall),
+                    'o' => Some(Mark::Item),
+                    '+' => Some(Mark::Active),
+                    _ => None,
+                })
+            }, |cave| format!("sand at rest {}", cave.count(day14::Structure::Sand))))
+        }
+        17 => Box::new(Stepper::new(day17::Chamber::new(leak(day17::generator(input))), |chamber| {
+            Frame::text(&chamber.to_string(), |c| match c {
+                '#' => Some(Mark::Item),
+                '|' | '+' | '-' => Some(Mark::Wall),
+                _ => None,
+            })
+        }, |chamber| format!("rocks {}  height {}", chamber.rocks(), chamber.height()))),
+        20 => {
+            let (rounds, key) = if part == 1 { (1, 1) } else { (10, day20::DECRYPTION_KEY) };
+            Box::new(Stepper::new(day20::Mixer::new(leak(day20::generator(input)), rounds, key), |mixer| {
+                let numbers = mixer.numbers();
+                let text = numbers.chunks(10).map(|row| row.iter().map(|n| format!("{:>16}", n)).collect::<String>());
+                Frame::text(&text.collect::<Vec<_>>().join("\n"), |_| None)
+            }, |_| String::new()))
+        }
+        23 => {
+            let elves = day23::Diffusion::new(&day23::generator(input), day23::RuleSet::standard());
+            Box::new(Stepper::new(elves, |elves| {
+                Frame::text(&elves.to_string(), |c| (c == '#').then_some(Mark::Item))
+            }, |elves| format!("empty tiles {}", elves.empty_tiles())))
+        }
+        24 => Box::new(Stepper::new(day24::Valley::new(&day24::generator(input)), |valley| {
+            Frame::text(&valley.to_string(), |c| match c {
+                '#' => Some(Mark::Wall),
+                'E' => Some(Mark::Active),
+                '.' => None,
+                _ => Some(Mark::Item),
+            })
+        }, |valley| format!("minute {}{}", valley.minute(), if valley.reached_goal() { "  goal reached" } else { "" }))),
+        _ => return None,
+    };
+    Some(view)
+}
+
+// y points up for the rope, the picture is flipped to have it up on screen too
+fn draw_rope(motions: &day09::Motions<'static, Point, 2>) -> Frame {
+    let rope = motions.state();
+    let knots = rope.knots();
+    let mut cells = HashMap::new();
+    for p in rope.visited(knots.len() - 1) {
+        cells.insert(*p, '#');
+    }
+    cells.insert([0, 0], 's');
+    for (i, k) in knots.iter().enumerate().rev() {
+        cells.insert(*k, if i == 0 { 'H' } else { char::from_digit(i as u32, 10).unwrap_or('T') });
+    }
+    let (min_x, max_x) = cells.keys().map(|p| p[0]).fold((0, 0), |(lo, hi), x| (lo.min(x), hi.max(x)));
+    let (min_y, max_y) = cells.keys().map(|p| p[1]).fold((0, 0), |(lo, hi), y| (lo.min(y), hi.max(y)));
+    let text = (min_y..=max_y).rev().map(|y| {
+        (min_x..=max_x).map(|x| cells.get(&[x, y]).copied().unwrap_or('.')).collect::<String>()
+    }).collect::<Vec<_>>();
+    Frame::text(&text.join("\n"), |c| match c {
+        'H' => Some(Mark::Active),
+        '#' => Some(Mark::Visited),
+        '.' => None,
+        _ => Some(Mark::Item),
+    })
+}
+
+// the beam is about to draw the active cell, the sprite is the frontier it is compared with
+fn draw_crt(cpu: &day10::Cpu<'static>) -> Frame {
+    let mut frame = Frame::text(&cpu.screen(), |c| (c == '#').then_some(Mark::Item));
+    let beam = cpu.next_cycle() as i64 - 1;
+    let row = beam / 40;
+    for x in cpu.x() - 1..=cpu.x() + 1 {
+        frame.marks.insert(Point::new(x as i64, row), Mark::Frontier);
+    }
+    frame.marks.insert(Point::new(beam % 40, row), Mark::Active);
+    frame
+}
+
+fn draw_search((grid, start, dest): &(Vec<Vec<char>>, Point, Point), replay: &Replay<Point>) -> Frame {
+    let text = grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>();
+    let mut frame = Frame::text(&text.join("\n"), |_| None);
+    for p in replay.visited() {
+        frame.marks.insert(*p, Mark::Visited);
+    }
+    for p in replay.frontier() {
+        frame.marks.insert(*p, Mark::Frontier);
+    }
+    for p in replay.path().unwrap_or_default() {
+        frame.marks.insert(*p, Mark::Path);
+    }
+    for p in replay.current().into_iter().chain([*start, *dest]) {
+        frame.marks.insert(p, Mark::Active);
+    }
+    frame
+}
+
+struct App {
+    title: String,
+    view: Box<dyn View>,
+    // grid cell in the top left corner, each screen cell shows zoom x zoom grid cells
+    offset: Point,
+    zoom: i64,
+    playing: bool,
+    speed: usize,
+    page: Point,
+}
+
+impl App {
+    fn draw(&mut self, f: &mut ratatui::Frame, frame: &Frame) {
+        let [main, status, help] = Layout::vertical([Constraint::Min(1), Constraint::Length(1), Constraint::Length(1)]).areas(f.area());
+        let block = Block::bordered().title(self.title.as_str());
+        let inner = block.inner(main);
+        self.page = Point::new(inner.width as i64, inner.height as i64);
+        f.render_widget(block, main);
+        f.render_widget(Viewport { frame, offset: self.offset, zoom: self.zoom }, inner);
+        let state = if self.view.is_done() { "done" } else if self.playing { "playing" } else { "paused" };
+        let line = format!(
+            "step {}  {} x{}  zoom 1:{}  at {},{}  {}",
+            self.view.steps(), state, self.speed, self.zoom, self.offset[0], self.offset[1], self.view.status()
+        );
+        f.render_widget(Paragraph::new(line), status);
+        f.render_widget(Paragraph::new(HELP).style(Style::new().fg(Color::DarkGray)), help);
+    }
+
+    fn scroll(&mut self, by: Point, frame: &Frame) {
+        let (min, max) = frame.cells.extents();
+        let to = self.offset + Point::new(by[0] * self.zoom, by[1] * self.zoom);
+        self.offset = Point::new(to[0].clamp(min[0], max[0].max(min[0])), to[1].clamp(min[1], max[1].max(min[1])));
+    }
+
+    // false to quit
+    fn handle(&mut self, key: KeyCode, frame: &Frame) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ') => self.playing = !self.playing,
+            KeyCode::Char('n') | KeyCode::Char('.') => {
+                self.playing = false;
+                self.view.step();
+            }
+            KeyCode::Char('b') | KeyCode::Char(',') => {
+                self.playing = false;
+                self.view.back();
+            }
+            KeyCode::Char('r') => {
+                self.playing = false;
+                self.view.restart();
+            }
+            KeyCode::Char(']') => self.speed = (self.speed * 2).min(MAX_SPEED),
+            KeyCode::Char('[') => self.speed = (self.speed / 2).max(1),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.zoom = (self.zoom / 2).max(1),
+            KeyCode::Char('-') => self.zoom = (self.zoom * 2).min(MAX_ZOOM),
+            KeyCode::Left | KeyCode::Char('h') => self.scroll(Point::new(-1, 0), frame),
+            KeyCode::Right | KeyCode::Char('l') => self.scroll(Point::new(1, 0), frame),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll(Point::new(0, -1), frame),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll(Point::new(0, 1), frame),
+            KeyCode::PageUp => self.scroll(Point::new(0, -self.page[1]), frame),
+            KeyCode::PageDown => self.scroll(Point::new(0, self.page[1]), frame),
+            KeyCode::Home => self.offset = frame.cells.extents().0,
+            _ => {}
+        }
+        true
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            let frame = self.view.frame();
+            terminal.draw(|f| self.draw(f, &frame))?;
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !self.handle(key.code, &frame) {
+                        return Ok(());
+                    }
+                }
+            } else if self.playing {
+                for _ in 0..self.speed {
+                    self.view.step();
+                }
+                self.playing = !self.view.is_done();
+            }
+        }
+    }
+}
+
+struct Viewport<'a> {
+    frame: &'a Frame,
+    offset: Point,
+    zoom: i64,
+}
+
+impl Widget for Viewport<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for row in 0..area.height {
+            for col in 0..area.width {
+                let corner = self.offset + Point::new(col as i64 * self.zoom, row as i64 * self.zoom);
+                if let Some((c, mark)) = self.frame.block(corner, self.zoom) {
+                    let style = mark.map(|m| m.style()).unwrap_or_default();
+                    buf[(area.x + col, area.y + row)].set_char(c).set_style(style);
+                }
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let mut input_file = None;
+    let (mut seed, mut size) = (0, 20);
+    let mut bfs = false;
+    let mut numbers = vec![];
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--input" => args.next().map(|f| input_file = Some(f)).is_some(),
+            "--seed" => args.next().and_then(|s| s.parse().ok()).map(|s| seed = s).is_some(),
+            "--size" => args.next().and_then(|s| s.parse().ok()).map(|s| size = s).is_some(),
+            "--bfs" => {
+                bfs = true;
+                true
+            }
+            _ => arg.parse::<u32>().map(|n| numbers.push(n)).is_ok(),
+        };
+        if !ok {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    }
+    let (day, part) = match numbers[..] {
+        [d] => (d, 1),
+        [d, p @ (1 | 2)] => (d, p as u8),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // the real input if there is one, a generated one otherwise
+    let path = input_file.map(Into::into).unwrap_or_else(|| input_path(day));
+    let (input, source) = match fs::read_to_string(&path) {
+        Ok(input) => (input, path.display().to_string()),
+        Err(_) => match inputgen::generate(day, seed, size) {
+            Some(input) => (input, format!("generated, seed {} size {}", seed, size)),
+            None => {
+                eprintln!("no input for day {}", day);
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    let Some(view) = view(day, part, bfs, &input) else {
+        eprintln!("day {} has nothing to step through\n{}", day, USAGE);
+        return ExitCode::FAILURE;
+    };
+    let name = registry().into_iter().find(|e| e.day == day).map(|e| e.title).unwrap_or_default();
+    let mut app = App {
+        title: format!(" Day {}: {}, part {} ({}) ", day, name, part, source),
+        view,
+        offset: Point::new(0, 0),
+        zoom: 1,
+        playing: false,
+        speed: 1,
+        page: Point::new(0, 0),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Cooperative cancellation for long searches. The runner installs a token on the thread that runs
+// a solution, searches call checkpoint() now and then and unwind once the token is cancelled.
+use std::cell::RefCell;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// the unwind payload, Entry::run turns it into a "cancelled" error instead of a panic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+#[derive(Debug, Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+thread_local! {
+    static CURRENT: RefCell<Option<Cancel>> = const { RefCell::new(None) };
+}
+
+impl Cancel {
+    pub fn new() -> Cancel {
+        Default::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    // resume_unwind skips the panic hook, a cancelled search is not worth a message
+    pub fn check(&self) {
+        if self.is_cancelled() {
+            panic::resume_unwind(Box::new(Cancelled));
+        }
+    }
+
+    // makes this the token of the current thread while f runs
+    pub fn install<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|c| c.replace(Some(self.clone())));
+        let restore = Restore(previous);
+        let result = f();
+        drop(restore);
+        result
+    }
+}
+
+struct Restore(Option<Cancel>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+    }
+}
+
+// the token of this thread, one that is never cancelled if nothing was installed. Searches that
+// fan out over rayon take it along and install it on the worker threads.
+pub fn current() -> Cancel {
+    CURRENT.with(|c| c.borrow().clone()).unwrap_or_default()
+}
+
+pub fn checkpoint() {
+    CURRENT.with(|c| {
+        if let Some(cancel) = c.borrow().as_ref() {
+            cancel.check();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_checkpoint() {
+        checkpoint();
+        let cancel = Cancel::new();
+        cancel.install(checkpoint);
+        cancel.cancel();
+        let result = panic::catch_unwind(|| cancel.install(checkpoint));
+        assert!(result.unwrap_err().downcast_ref::<Cancelled>().is_some());
+        // the token is gone again after unwinding
+        checkpoint();
+        assert!(!current().is_cancelled());
+        assert!(cancel.install(current).is_cancelled());
+    }
+}
@@ -1,8 +1,8 @@
 use itertools::Itertools;
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 type Data = Vec<i32>;
 
-#[aoc_generator(day1)]
 pub fn generator(input: &str) -> Data {
     input
         .split("\n\n")
@@ -12,15 +12,34 @@ pub fn generator(input: &str) -> Data {
         ).collect()
 }
 
-#[aoc(day1, part1)]
 pub fn part1(inputs: &Data) -> i32 {
     *inputs.iter().max().unwrap()
 }
 
-#[aoc(day1, part2)]
 pub fn part2(inputs: &Data) -> i32 {
     inputs.iter().sorted().rev().take(3).sum()
 }
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Calorie Counting";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
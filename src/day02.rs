@@ -1,73 +1,172 @@
-type Data = Vec<(char, char)>;
+use std::str::FromStr;
 
-#[aoc_generator(day2)]
-pub fn generator(input: &str) -> Data {
+use thiserror::Error;
+use crate::solution::{Answer, IntoAnswer, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Rock = 0,
+    Paper = 1,
+    Scissors = 2,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    pub fn score(self) -> u32 {
+        self as u32 + 1
+    }
+}
+
+impl FromStr for Shape {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" | "X" => Ok(Shape::Rock),
+            "B" | "Y" => Ok(Shape::Paper),
+            "C" | "Z" => Ok(Shape::Scissors),
+            _ => Err(ParseError::InvalidShape(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub fn score(self) -> u32 {
+        self as u32 * 3
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X" => Ok(Outcome::Lose),
+            "Y" => Ok(Outcome::Draw),
+            "Z" => Ok(Outcome::Win),
+            _ => Err(ParseError::InvalidOutcome(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("invalid shape: {0:?}")]
+    InvalidShape(String),
+    #[error("invalid outcome: {0:?}")]
+    InvalidOutcome(String),
+    #[error("malformed line: {0:?}")]
+    MalformedLine(String),
+}
+
+// n shapes in a circle, every shape beats the (n - 1) / 2 shapes before it.
+// Rock, Paper, Scissors is the game with 3 shapes, for Rock, Paper, Scissors, Lizard, Spock
+// order the shapes Rock, Spock, Paper, Lizard, Scissors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CyclicGame {
+    shapes: usize,
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum GameError {
+    #[error("a cyclic game needs an odd number of shapes, not {0}")]
+    EvenShapes(usize),
+}
+
+impl CyclicGame {
+    // with an even number of shapes one of them would neither beat nor lose to the one opposite
+    pub fn new(shapes: usize) -> Result<CyclicGame, GameError> {
+        if shapes % 2 == 1 {
+            Ok(CyclicGame { shapes })
+        } else {
+            Err(GameError::EvenShapes(shapes))
+        }
+    }
+
+    pub fn shapes(&self) -> usize {
+        self.shapes
+    }
+
+    pub fn outcome(&self, mine: usize, theirs: usize) -> Outcome {
+        match (mine + self.shapes - theirs) % self.shapes {
+            0 => Outcome::Draw,
+            d if d <= (self.shapes - 1) / 2 => Outcome::Win,
+            _ => Outcome::Lose,
+        }
+    }
+
+    pub fn response(&self, theirs: usize, outcome: Outcome) -> usize {
+        match outcome {
+            Outcome::Draw => theirs,
+            Outcome::Win => (theirs + 1) % self.shapes,
+            Outcome::Lose => (theirs + self.shapes - 1) % self.shapes,
+        }
+    }
+}
+
+pub const RPS: CyclicGame = CyclicGame { shapes: 3 };
+
+// the second column is read as a shape in part 1 and as an outcome in part 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strategy {
+    pub opponent: Shape,
+    pub response: Shape,
+    pub outcome: Outcome,
+}
+
+type Data = Vec<Strategy>;
+
+pub fn generator(input: &str) -> Result<Data, ParseError> {
     input.lines().map(|l| {
-        (l.chars().nth(0).unwrap(), l.chars().nth(2).unwrap())
+        match l.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [a, b] => Ok(Strategy { opponent: a.parse()?, response: b.parse()?, outcome: b.parse()? }),
+            _ => Err(ParseError::MalformedLine(l.to_string())),
+        }
     }).collect()
 }
 
-#[aoc(day2, part1)]
-pub fn part1(inputs: &Data) -> i32 {
+pub fn part1(inputs: &Data) -> u32 {
     inputs
         .iter()
-        .map(|(o, p)| {
-            let res = match (o, p) {
-                ('A', 'Y') => 6,
-                ('B', 'Z') => 6,
-                ('C', 'X') => 6,
-                ('A', 'X') => 3,
-                ('B', 'Y') => 3,
-                ('C', 'Z') => 3,
-                _ => 0
-            };
-            let choice = match p {
-                'X' => 1,
-                'Y' => 2,
-                'Z' => 3,
-                _ => unreachable!()
-            };
-            res + choice
-        })
+        .map(|s| s.response.score() + RPS.outcome(s.response as usize, s.opponent as usize).score())
         .sum()
 }
 
-#[aoc(day2, part2)]
-pub fn part2(inputs: &Data) -> i32 {
+pub fn part2(inputs: &Data) -> u32 {
     inputs
         .iter()
-        .map(|(o, p)| {
-            let n = match(o, p) {
-                ('A', 'X') => 'Z',
-                ('B', 'X') => 'X',
-                ('C', 'X') => 'Y',
-                ('A', 'Y') => 'X',
-                ('B', 'Y') => 'Y',
-                ('C', 'Y') => 'Z',
-                ('A', 'Z') => 'Y',
-                ('B', 'Z') => 'Z',
-                ('C', 'Z') => 'X',
-                _ => unreachable!()
-            };
-            let res = match (o, n) {
-                ('A', 'Y') => 6,
-                ('B', 'Z') => 6,
-                ('C', 'X') => 6,
-                ('A', 'X') => 3,
-                ('B', 'Y') => 3,
-                ('C', 'Z') => 3,
-                _ => 0
-            };
-            let choice = match n {
-                'X' => 1,
-                'Y' => 2,
-                'Z' => 3,
-                _ => unreachable!()
-            };
-            res + choice
-        })
+        .map(|s| Shape::ALL[RPS.response(s.opponent as usize, s.outcome)].score() + s.outcome.score())
         .sum()
 }
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u32 = 2;
+    const TITLE: &'static str = "Rock Paper Scissors";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        generator(input).map_err(|e| e.to_string())
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,11 +177,27 @@ C Z";
 
     #[test]
     pub fn test1() {
-        assert_eq!(part1(&generator(&SAMPLE)), 15)
+        assert_eq!(part1(&generator(&SAMPLE).unwrap()), 15)
     }
 
     #[test]
     pub fn test2() {
-        assert_eq!(part2(&generator(&SAMPLE)), 12)
+        assert_eq!(part2(&generator(&SAMPLE).unwrap()), 12)
+    }
+
+    #[test]
+    pub fn test_rpsls() {
+        let (rock, spock, paper, lizard, scissors) = (0, 1, 2, 3, 4);
+        let game = CyclicGame::new(5).unwrap();
+        assert_eq!(game.outcome(paper, rock), Outcome::Win);
+        assert_eq!(game.outcome(lizard, spock), Outcome::Win);
+        assert_eq!(game.outcome(scissors, lizard), Outcome::Win);
+        assert_eq!(game.outcome(spock, paper), Outcome::Lose);
+        assert_eq!(game.outcome(game.response(lizard, Outcome::Lose), lizard), Outcome::Lose);
+        assert_eq!(CyclicGame::new(3), Ok(RPS));
+        assert_eq!(CyclicGame::new(4), Err(GameError::EvenShapes(4)));
+        assert_eq!(CyclicGame::new(0), Err(GameError::EvenShapes(0)));
+        assert_eq!(generator("A Y\nD X"), Err(ParseError::InvalidShape("D".to_string())));
+        assert_eq!(generator("A"), Err(ParseError::MalformedLine("A".to_string())));
     }
 }
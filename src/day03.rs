@@ -1,46 +1,94 @@
-use itertools::Itertools;
+use thiserror::Error;
+use crate::solution::{Answer, IntoAnswer, Solution};
 
-type Data = Vec<(String, String)>;
+// one bit per item type, bit n is set for the item with priority n
+type Rucksack = (u64, u64);
+type Data = Vec<Rucksack>;
 
-pub fn priority(c: char) -> i32 {
-    if c.is_lowercase() {
-        (c as u8 - 'a' as u8 + 1) as i32
-    } else {
-        (c as u8 - 'A' as u8 + 27) as i32
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum RucksackError {
+    #[error("invalid item: {0:?}")]
+    InvalidItem(char),
+    #[error("compartments differ in size: {0:?}")]
+    UnevenCompartments(String),
+    #[error("expected exactly one common item, found {0}")]
+    NoUniqueItem(u32),
+    #[error("{0} rucksacks can not be split into groups of {1}")]
+    IncompleteGroup(usize, usize),
+}
+
+pub fn priority(c: char) -> Result<u32, RucksackError> {
+    match c {
+        'a'..='z' => Ok(c as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Ok(c as u32 - 'A' as u32 + 27),
+        _ => Err(RucksackError::InvalidItem(c)),
+    }
+}
+
+pub fn item_mask(items: &str) -> Result<u64, RucksackError> {
+    items.chars().try_fold(0, |acc, c| Ok(acc | 1 << priority(c)?))
+}
+
+// priority of the single item present in all masks
+pub fn common_priority(masks: impl IntoIterator<Item = u64>) -> Result<u32, RucksackError> {
+    let common = masks.into_iter().fold(u64::MAX, |acc, m| acc & m);
+    match common.count_ones() {
+        1 => Ok(common.trailing_zeros()),
+        n => Err(RucksackError::NoUniqueItem(n)),
     }
 }
 
-#[aoc_generator(day3)]
-pub fn generator(input: &str) -> Data {
+pub fn generator(input: &str) -> Result<Data, RucksackError> {
     input
         .lines()
         .map(|l| {
+            // items are ascii, so once they all check out the byte length counts them
+            l.chars().try_for_each(|c| priority(c).map(|_| ()))?;
+            if l.len() % 2 != 0 {
+                return Err(RucksackError::UnevenCompartments(l.to_string()));
+            }
             let (a, b) = l.split_at(l.len() / 2);
-            (a.to_string(), b.to_string())
+            Ok((item_mask(a)?, item_mask(b)?))
         })
         .collect()
 }
 
-#[aoc(day3, part1)]
-pub fn part1(inputs: &Data) -> i32 {
+pub fn badges(inputs: &Data, group_size: usize) -> Result<u32, RucksackError> {
+    if group_size == 0 || !inputs.len().is_multiple_of(group_size) {
+        return Err(RucksackError::IncompleteGroup(inputs.len(), group_size));
+    }
     inputs
-        .iter()
-        .map(|(a, b)| {
-            priority(a.chars().find(|x| b.contains(*x)).unwrap())
-        })
+        .chunks(group_size)
+        .map(|group| common_priority(group.iter().map(|(a, b)| a | b)))
         .sum()
 }
 
-#[aoc(day3, part2)]
-pub fn part2(inputs: &Data) -> i32 {
-    inputs.iter().tuples::<(_, _, _)>().map(|(a, b, c)| {
-        let mut joined = a.0.to_string();
-        joined.push_str(a.1.as_str());
-        priority(joined.chars().into_iter().find(|x|
-            (b.0.contains(*x) || b.1.contains(*x)) &&
-            (c.0.contains(*x) || c.1.contains(*x))
-        ).unwrap())
-    }).sum()
+pub fn part1(inputs: &Data) -> Result<u32, RucksackError> {
+    inputs.iter().map(|&(a, b)| common_priority([a, b])).sum()
+}
+
+pub fn part2(inputs: &Data) -> Result<u32, RucksackError> {
+    badges(inputs, 3)
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u32 = 3;
+    const TITLE: &'static str = "Rucksack Reorganization";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        generator(input).map_err(|e| e.to_string())
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
 }
 
 #[cfg(test)]
@@ -56,11 +104,22 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     pub fn test1() {
-        assert_eq!(part1(&generator(&SAMPLE)), 157)
+        assert_eq!(part1(&generator(&SAMPLE).unwrap()), Ok(157))
     }
 
     #[test]
     pub fn test2() {
-        assert_eq!(part2(&generator(&SAMPLE)), 70)
+        assert_eq!(part2(&generator(&SAMPLE).unwrap()), Ok(70))
+    }
+
+    #[test]
+    pub fn test_errors() {
+        assert_eq!(generator("ab1c"), Err(RucksackError::InvalidItem('1')));
+        assert_eq!(generator("abc"), Err(RucksackError::UnevenCompartments("abc".to_string())));
+        assert_eq!(generator("aéb"), Err(RucksackError::InvalidItem('é')));
+        assert_eq!(generator("éa"), Err(RucksackError::InvalidItem('é')));
+        assert_eq!(part1(&generator("abcd").unwrap()), Err(RucksackError::NoUniqueItem(0)));
+        assert_eq!(badges(&generator(&SAMPLE).unwrap(), 4), Err(RucksackError::IncompleteGroup(6, 4)));
+        assert_eq!(badges(&generator(&SAMPLE).unwrap(), 6), Err(RucksackError::NoUniqueItem(0)));
     }
 }
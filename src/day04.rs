@@ -1,9 +1,9 @@
 use range_ext::intersect::*;
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 type Data = Vec<((i32, i32),(i32,i32))>;
 
 
-#[aoc_generator(day4)]
 pub fn generator(input: &str) -> Data {
     input
         .lines()
@@ -16,7 +16,6 @@ pub fn generator(input: &str) -> Data {
         .collect()
 }
 
-#[aoc(day4, part1)]
 pub fn part1(inputs: &Data) -> i32 {
     inputs.into_iter().map(|&((a, b),(x,y))| {
         match (a..b+1).intersect(&(x..y+1)) {
@@ -26,7 +25,6 @@ pub fn part1(inputs: &Data) -> i32 {
     }).sum()
 }
 
-#[aoc(day4, part2)]
 pub fn part2(inputs: &Data) -> i32 {
     inputs.into_iter().map(|&((a, b),(x,y))| {
         match (a..b+1).intersect(&(x..y+1)) {
@@ -36,6 +34,26 @@ pub fn part2(inputs: &Data) -> i32 {
     }).sum()
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u32 = 4;
+    const TITLE: &'static str = "Camp Cleanup";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
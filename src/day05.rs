@@ -1,11 +1,11 @@
 use std::collections::VecDeque;
 use regex::Regex;
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 type Statement = (usize, usize, usize);
 type Data = ([VecDeque::<char>; 9], Vec::<Statement>);
 
 
-#[aoc_generator(day5)]
 pub fn generator(input: &str) -> Data {
     let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
     let lines: Vec<&str> = input.lines().collect();
@@ -26,7 +26,6 @@ pub fn generator(input: &str) -> Data {
     (stacks, stmt)
 }
 
-#[aoc(day5, part1)]
 pub fn part1(inputs: &Data) -> String {
     let (mut stacks, stmts) = inputs.clone();
     stmts.iter().for_each(|s| {
@@ -43,7 +42,6 @@ pub fn part1(inputs: &Data) -> String {
     })
 }
 
-#[aoc(day5, part2)]
 pub fn part2(inputs: &Data) -> String {
     let (mut stacks, stmts) = inputs.clone();
     stmts.iter().for_each(|s| {
@@ -62,6 +60,26 @@ pub fn part2(inputs: &Data) -> String {
     })
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u32 = 5;
+    const TITLE: &'static str = "Supply Stacks";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,55 +1,149 @@
- use std::collections::HashSet;
+ use std::collections::HashMap;
+ use std::hash::Hash;
+ use std::io::{self, BufReader, Read};
  use itertools::Itertools;
+ use crate::solution::{Answer, IntoAnswer, Solution, Variant};
 
-#[aoc_generator(day6)]
 pub fn generator(input: &str) -> String {
     input.to_string()
 }
 
-#[aoc(day6, part1)]
 pub fn part1(inputs: &String) -> usize {
     solve(inputs, 4)
 }
 
- #[aoc(day6, part1, noset)]
  pub fn part1_noset(inputs: &String) -> usize {
      solve2(inputs, 4)
  }
 
-#[aoc(day6, part2)]
 pub fn part2(inputs: &String) -> usize {
     solve(inputs, 14)
 }
 
- #[aoc(day6, part2, noset)]
  pub fn part2_noset(inputs: &String) -> usize {
      solve2(inputs, 14)
  }
 
- pub fn solve(inputs: &String, len: usize) -> usize {
-     let charin = inputs.chars().collect_vec();
-     charin.windows(len).enumerate().find(|(_, window)| {
-         let mut set = HashSet::<char>::new();
-         let mut found = true;
-         window.iter().for_each(|&c| {
-             if set.contains(&c) {
-                 found = false;
-             } else {
-                 set.insert(c);
+ // Sliding window over any symbol type: the window start only ever moves forward, past the
+ // last occurrence of the incoming symbol, so every symbol is looked at once.
+ pub struct MarkerDetector<T> {
+     len: usize,
+     last_seen: HashMap<T, usize>,
+     start: usize,
+     pos: usize,
+ }
+
+ impl<T: Copy + Eq + Hash> MarkerDetector<T> {
+     pub fn new(len: usize) -> MarkerDetector<T> {
+         MarkerDetector { len, last_seen: HashMap::new(), start: 0, pos: 0 }
+     }
+
+     // feed the next symbol, returns the number of symbols read so far if they end a marker
+     pub fn push(&mut self, symbol: T) -> Option<usize> {
+         if let Some(seen) = self.last_seen.insert(symbol, self.pos) {
+             self.start = self.start.max(seen + 1);
+         }
+         self.pos += 1;
+         if self.pos - self.start >= self.len {
+             Some(self.pos)
+         } else {
+             None
+         }
+     }
+ }
+
+ pub fn markers<T, I>(symbols: I, len: usize) -> impl Iterator<Item = io::Result<usize>>
+     where
+         T: Copy + Eq + Hash,
+         I: Iterator<Item = io::Result<T>>,
+ {
+     let mut detector = MarkerDetector::new(len);
+     symbols.filter_map(move |s| match s {
+         Ok(s) => detector.push(s).map(Ok),
+         Err(e) => Some(Err(e)),
+     })
+ }
+
+ pub fn byte_markers<R: Read>(reader: R, len: usize) -> impl Iterator<Item = io::Result<usize>> {
+     markers(BufReader::new(reader).bytes(), len)
+ }
+
+ pub fn char_markers<R: Read>(reader: R, len: usize) -> impl Iterator<Item = io::Result<usize>> {
+     markers(Utf8Chars { bytes: BufReader::new(reader).bytes().peekable() }, len)
+ }
+
+ // Decodes a byte stream without loading it, broken sequences become U+FFFD.
+ struct Utf8Chars<I: Iterator<Item = io::Result<u8>>> {
+     bytes: std::iter::Peekable<I>,
+ }
+
+ impl<I: Iterator<Item = io::Result<u8>>> Iterator for Utf8Chars<I> {
+     type Item = io::Result<char>;
+
+     fn next(&mut self) -> Option<Self::Item> {
+         let lead = match self.bytes.next()? {
+             Ok(b) => b,
+             Err(e) => return Some(Err(e)),
+         };
+         let width = match lead {
+             0x00..=0x7f => return Some(Ok(lead as char)),
+             0xc0..=0xdf => 2,
+             0xe0..=0xef => 3,
+             0xf0..=0xf7 => 4,
+             _ => return Some(Ok(char::REPLACEMENT_CHARACTER)),
+         };
+         let mut buf = vec![lead];
+         while buf.len() < width {
+             match self.bytes.peek() {
+                 Some(Ok(b)) if b & 0xc0 == 0x80 => buf.push(*b),
+                 _ => return Some(Ok(char::REPLACEMENT_CHARACTER)),
              }
-         });
-         return found
-     }).unwrap().0 + len
+             self.bytes.next();
+         }
+         Some(Ok(std::str::from_utf8(&buf).ok().and_then(|s| s.chars().next()).unwrap_or(char::REPLACEMENT_CHARACTER)))
+     }
+ }
+
+ pub fn solve(inputs: &str, len: usize) -> usize {
+     char_markers(inputs.as_bytes(), len).next().unwrap().unwrap()
  }
 
- pub fn solve2(inputs: &String, len: usize) -> usize {
+ pub fn solve2(inputs: &str, len: usize) -> usize {
      let charin = inputs.chars().collect_vec();
      charin.windows(len).position(|arr| arr.iter().all_unique()).unwrap() + len
  }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+    const TITLE: &'static str = "Tuning Trouble";
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &String) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &String) -> Answer {
+        part2(input).into_answer()
+    }
+
+    fn variants() -> Vec<Variant<String>> {
+        vec![
+            Variant { part: 1, name: "noset", run: |input| part1_noset(input).into_answer() },
+            Variant { part: 2, name: "noset", run: |input| part2_noset(input).into_answer() },
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::differential::assert_agree;
     #[test]
     pub fn test1() {
         assert_eq!(part1(&"bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()), 5);
@@ -76,4 +170,19 @@ mod tests {
         assert_eq!(part2_noset(&"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string()), 29);
         assert_eq!(part2_noset(&"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string()), 26);
     }
+    #[test]
+    pub fn test_all_markers() {
+        let found = byte_markers("aabcab".as_bytes(), 3).collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(found, vec![4, 5, 6]);
+        let found = char_markers("ääöüäx".as_bytes(), 3).collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(found, vec![4, 5, 6]);
+    }
+    #[test]
+    pub fn test_variants() {
+        let entries = Day06::entries();
+        for (sample, marker, message) in [("mjqjpqmgbljsphdztnvjfqwrcgsmlb", "7", "19"), ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", "11", "26")] {
+            assert_eq!(assert_agree(&entries, 6, 1, sample), Ok(marker.to_string()));
+            assert_eq!(assert_agree(&entries, 6, 2, sample), Ok(message.to_string()));
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use core::cell::Cell;
+use crate::solution::{Answer, IntoAnswer, Solution, Variant};
 
 struct Node {
     name: Vec<u8>,
@@ -143,12 +144,10 @@ fn parse<'a>(input: &mut impl Iterator<Item = &'a str>) -> Vec<u32> {
 }
 
 
-#[aoc_generator(day7)]
 pub fn generator(input: &str) -> String {
     input.to_string()
 }
 
-#[aoc(day7, part1)]
 pub fn part1(inputs: &str) -> u32 {
     parse(&mut inputs.lines())
         .into_iter()
@@ -156,21 +155,18 @@ pub fn part1(inputs: &str) -> u32 {
         .sum()
 }
 
-#[aoc(day7, part1, tree)]
 pub fn part1_true(inputs: &str) -> u32 {
     let fs = parse_fs(inputs);
     fs.update_dir_sizes(0);
     fs.iter_dirs().map(|n| n.size.get()).filter(|&s| s < 100_000).sum()
 }
 
-#[aoc(day7, part2)]
 pub fn part2(inputs: &str) -> u32 {
     let dirsizes = parse(&mut inputs.lines());
     let missing = 30_000_000 - (70_000_000 - dirsizes.last().unwrap());
     dirsizes.into_iter().filter(|&size| size >= missing).min().unwrap()
 }
 
-#[aoc(day7, part2, tree)]
 pub fn part2_true(inputs: &str) -> u32 {
     let fs = parse_fs(inputs);
     fs.update_dir_sizes(0);
@@ -178,9 +174,37 @@ pub fn part2_true(inputs: &str) -> u32 {
     fs.iter_dirs().map(|n| n.size.get()).filter(|&s| s >= missing).min().unwrap()
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u32 = 7;
+    const TITLE: &'static str = "No Space Left On Device";
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &String) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &String) -> Answer {
+        part2(input).into_answer()
+    }
+
+    fn variants() -> Vec<Variant<String>> {
+        vec![
+            Variant { part: 1, name: "tree", run: |input| part1_true(input).into_answer() },
+            Variant { part: 2, name: "tree", run: |input| part2_true(input).into_answer() },
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::differential::assert_agree;
 
     const SAMPLE: &str = "$ cd /
 $ ls
@@ -215,4 +239,11 @@ $ ls
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 24933642)
     }
+
+    #[test]
+    pub fn test_variants() {
+        let entries = Day07::entries();
+        assert_eq!(assert_agree(&entries, 7, 1, SAMPLE), Ok("95437".to_string()));
+        assert_eq!(assert_agree(&entries, 7, 2, SAMPLE), Ok("24933642".to_string()));
+    }
 }
@@ -1,50 +1,87 @@
-use itertools::Itertools;
-use itertools::FoldWhile::{Continue, Done};
-use aoc::{Grid, point_add, point_mul};
+use aoc::{Grid, GridView, Point, DIRECTIONS};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 type Data = Vec<Vec<i8>>;
 
-#[aoc_generator(day8)]
+// Per-tree result, the arrays are indexed like aoc::DIRECTIONS
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VisibilityInfo {
+    pub height: i8,
+    pub visible_from: [bool; 4],
+    pub viewing_distance: [i32; 4],
+}
+
+impl VisibilityInfo {
+    pub fn visible(&self) -> bool {
+        self.visible_from.iter().any(|&v| v)
+    }
+
+    pub fn scenic_score(&self) -> i32 {
+        self.viewing_distance.iter().product()
+    }
+}
+
 pub fn generator(input: &str) -> Data {
-    aoc::parse_grid_to(input.lines().collect::<Vec<_>>().as_slice(), |x| x.to_digit(10).unwrap() as i8)
+    aoc::GridParser::new().parse_dense(input, |x| x.to_digit(10).unwrap() as i8).grid
 }
 
-#[aoc(day8, part1)]
-pub fn part1(inputs: &Data) -> usize {
-    inputs.points().map(|p| {
-        let size = inputs.get_value(p).unwrap();
-        aoc::DIRECTIONS.into_iter().map(|d| {
-            (0..).into_iter().fold_while(0, |_, n| {
-                return if let Some(other) = inputs.get_value(point_add(p, point_mul(d, n + 1))) {
-                    if other >= size {
-                        return Done(0)
+// One sweep per direction: every line is walked starting at the edge the trees look towards,
+// keeping a stack of the trees seen so far with strictly decreasing heights.
+pub fn visibility(inputs: &Data) -> Vec<Vec<VisibilityInfo>> {
+    let mut result = inputs.iter()
+        .map(|row| row.iter().map(|&height| VisibilityInfo { height, ..Default::default() }).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for (i, &d) in DIRECTIONS.iter().enumerate() {
+        let edges = inputs.points().filter(|&p| inputs.get_value(p + d).is_none()).collect::<Vec<Point>>();
+        for edge in edges {
+            let mut stack: Vec<(i8, i32)> = vec![];
+            for (n, (p, height)) in inputs.ray(edge + d, -d).enumerate() {
+                let n = n as i32;
+                while stack.last().map(|&(h, _)| h < height).unwrap_or(false) {
+                    stack.pop();
+                }
+                let mut info = result.get_value(p).unwrap();
+                match stack.last() {
+                    Some(&(_, blocker)) => info.viewing_distance[i] = n - blocker,
+                    None => {
+                        info.visible_from[i] = true;
+                        info.viewing_distance[i] = n;
                     }
-                    Continue(0)
-                } else {
-                    Done(1)
                 }
-            }).into_inner()
-        }).any(|vis| vis == 1)
-    }).filter(|&b| b).count()
+                result.set_value(p, info);
+                stack.push((height, n));
+            }
+        }
+    }
+    result
+}
+
+pub fn part1(inputs: &Data) -> usize {
+    visibility(inputs).iter().flatten().filter(|v| v.visible()).count()
 }
 
-#[aoc(day8, part2)]
 pub fn part2(inputs: &Data) -> i32 {
-    inputs.points().map(|p| {
-        let size = inputs.get_value(p).unwrap();
-        aoc::DIRECTIONS.into_iter().map(|d| {
-            (0..).into_iter().fold_while(0, |dist, n| {
-                return if let Some(other) = inputs.get_value(point_add(p, point_mul(d, n + 1))) {
-                    if other >= size {
-                        return Done(dist + 1)
-                    }
-                    Continue(dist + 1)
-                } else {
-                    Done(dist)
-                }
-            }).into_inner()
-        }).product()
-    }).max().unwrap()
+    visibility(inputs).iter().flatten().map(|v| v.scenic_score()).max().unwrap()
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u32 = 8;
+    const TITLE: &'static str = "Treetop Tree House";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
 }
 
 #[cfg(test)]
@@ -66,4 +103,12 @@ mod tests {
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 8)
     }
+
+    #[test]
+    pub fn test_info() {
+        let info = visibility(&generator(&SAMPLE)).get_value(Point::new(2, 3)).unwrap();
+        assert_eq!(info.height, 5);
+        assert_eq!(info.viewing_distance, [1, 2, 2, 2]);
+        assert_eq!(info.visible_from, [true, false, false, true]);
+    }
 }
@@ -1,79 +1,198 @@
 use std::collections::HashSet;
-use itertools::Itertools;
 use aoc::*;
+use aoc::sim::{self, Simulation};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 type Data = Vec<(Point, i32)>;
 
-const DEBUG: bool = false;
-
-#[aoc_generator(day9)]
 pub fn generator(input: &str) -> Data {
     input
         .lines()
         .map(|l| {
             let parts = l.split_whitespace().collect::<Vec<_>>();
-            let d = match parts[0].chars().nth(0).unwrap() {
-                'R' => RIGHT,
-                'L' => LEFT,
-                'U' => UP,
-                'D' => DOWN,
-                _ => unreachable!()
-            };
+            let d = Dir4::from_char(parts[0].chars().next().unwrap()).unwrap().as_point(YAxis::Up);
             (d, parts[1].parse().unwrap())
         })
         .collect()
 }
 
-#[aoc(day9, part1)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Follow {
+    // stay touching including diagonals, step diagonally to catch up
+    Chebyshev,
+    // stay orthogonally adjacent, only ever step along one axis
+    Manhattan,
+    // when not touching anymore, jump to where the knot ahead just was
+    Lagged,
+}
+
+impl Follow {
+    fn follow<const N: usize>(&self, ahead_prev: [i64; N], ahead: [i64; N], knot: [i64; N]) -> [i64; N] {
+        let d: [i64; N] = std::array::from_fn(|i| ahead[i] - knot[i]);
+        match self {
+            Follow::Chebyshev | Follow::Lagged if d.iter().all(|c| c.abs() <= 1) => knot,
+            Follow::Chebyshev => std::array::from_fn(|i| knot[i] + d[i].signum()),
+            Follow::Lagged => ahead_prev,
+            Follow::Manhattan if d.iter().map(|c| c.abs()).sum::<i64>() <= 1 => knot,
+            Follow::Manhattan => {
+                let axis = (0..N).max_by_key(|&i| d[i].abs()).unwrap();
+                std::array::from_fn(|i| if i == axis { knot[i] + d[i].signum() } else { knot[i] })
+            }
+        }
+    }
+}
+
+// Knots are arrays of any dimension, moves can also be given as Point or Vec3. Every knot keeps its own set of visited positions,
+// the positions after every step are only kept when asked for with with_trail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rope<const N: usize> {
+    knots: Vec<[i64; N]>,
+    rule: Follow,
+    visited: Vec<HashSet<[i64; N]>>,
+    trail: Option<Vec<Vec<[i64; N]>>>,
+}
+
+impl<const N: usize> Rope<N> {
+    pub fn new(len: usize, rule: Follow) -> Rope<N> {
+        let knots = vec![[0; N]; len];
+        Rope {
+            visited: knots.iter().map(|&k| HashSet::from([k])).collect(),
+            trail: None,
+            knots,
+            rule,
+        }
+    }
+
+    pub fn with_trail(mut self) -> Rope<N> {
+        self.trail = Some(vec![self.knots.clone()]);
+        self
+    }
+
+    pub fn step(&mut self, direction: [i64; N]) {
+        let mut prev = self.knots[0];
+        self.knots[0] = std::array::from_fn(|i| prev[i] + direction[i]);
+        for i in 1..self.knots.len() {
+            let current = self.knots[i];
+            self.knots[i] = self.rule.follow(prev, self.knots[i - 1], current);
+            prev = current;
+        }
+        for (visited, knot) in self.visited.iter_mut().zip(self.knots.iter()) {
+            visited.insert(*knot);
+        }
+        if let Some(trail) = &mut self.trail {
+            trail.push(self.knots.clone());
+        }
+    }
+
+    pub fn apply<D: Copy + Into<[i64; N]>>(&mut self, moves: &[(D, i32)]) {
+        for &(direction, distance) in moves {
+            (0..distance).for_each(|_| self.step(direction.into()));
+        }
+    }
+
+    pub fn knots(&self) -> &[[i64; N]] {
+        &self.knots
+    }
+
+    pub fn visited(&self, knot: usize) -> &HashSet<[i64; N]> {
+        &self.visited[knot]
+    }
+
+    pub fn visited_counts(&self) -> Vec<usize> {
+        self.visited.iter().map(|v| v.len()).collect()
+    }
+
+    // positions of all knots after every step, starting with the state with_trail was called in
+    pub fn trail(&self) -> Option<&[Vec<[i64; N]>]> {
+        self.trail.as_deref()
+    }
+}
+
+// a list of moves played back one unit step at a time
+pub struct Motions<'a, D, const N: usize> {
+    rope: Rope<N>,
+    moves: &'a [(D, i32)],
+    next: usize,
+    taken: i32,
+}
+
+impl<'a, D: Copy + Into<[i64; N]>, const N: usize> Motions<'a, D, N> {
+    pub fn new(rope: Rope<N>, moves: &'a [(D, i32)]) -> Motions<'a, D, N> {
+        let mut motions = Motions { rope, moves, next: 0, taken: 0 };
+        motions.skip_finished();
+        motions
+    }
+
+    fn skip_finished(&mut self) {
+        while self.next < self.moves.len() && self.taken >= self.moves[self.next].1 {
+            self.next += 1;
+            self.taken = 0;
+        }
+    }
+}
+
+impl<D: Copy + Into<[i64; N]>, const N: usize> Simulation for Motions<'_, D, N> {
+    type State = Rope<N>;
+    type Snapshot = (Rope<N>, usize, i32);
+
+    fn step(&mut self) {
+        if let Some(&(direction, _)) = self.moves.get(self.next) {
+            self.rope.step(direction.into());
+            self.taken += 1;
+            self.skip_finished();
+        }
+    }
+
+    fn state(&self) -> &Rope<N> {
+        &self.rope
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.rope.clone(), self.next, self.taken)
+    }
+
+    fn restore(&mut self, (rope, next, taken): &Self::Snapshot) {
+        (self.rope, self.next, self.taken) = (rope.clone(), *next, *taken);
+    }
+
+    fn is_done(&self) -> bool {
+        self.next >= self.moves.len()
+    }
+}
+
 pub fn part1(inputs: &Data) -> usize {
     run(inputs, 1)
 }
 
 
-#[aoc(day9, part2)]
 pub fn part2(inputs: &Data) -> usize {
     run(inputs, 9)
 }
 
 pub fn run(inputs: &Data, tail_len: usize) -> usize {
-    let mut positions: Vec<Point> = vec![[0,0]; tail_len+1];
-    let visited: HashSet<Point> = HashSet::new();
-
-    inputs.into_iter().fold(visited, |visited, &(direction, distance)| {
-        (0..distance).into_iter().fold(visited, |mut visited, _| {
-            positions[0] = point_add(positions[0], direction);
-            (1..=tail_len).into_iter().for_each(|i| {
-                let current_head = positions[i-1];
-                let current_tail = &mut positions[i];
-
-                if !neighbors_incl_diagonals(current_head).contains(current_tail) && !current_head.eq(current_tail) {
-                    if i == tail_len {
-                        visited.insert(*current_tail);
-                    }
-                    *current_tail = point_add(*current_tail, point_signum(point_sub(current_head, *current_tail)));
-                }
-            });
-
-            if DEBUG {
-                for y in -15..=6 {
-                    for x in -11..=15 {
-                        match (0..tail_len-1).position(|i| positions[i] == [x, y]) {
-                            Some(0) => print!("H"),
-                            Some(i) => print!("{}", i+1),
-                            _ => print!(".")
-                        }
-                    }
-                    println!()
-                }
-                println!();
-            }
+    let mut motions = Motions::new(Rope::new(tail_len + 1, Follow::Chebyshev), inputs);
+    sim::run_to_end(&mut motions);
+    motions.state().visited(tail_len).len()
+}
 
-            if positions[tail_len] != [0,0] {
-                visited.insert(positions[tail_len]);
-            }
-            visited
-        })
-    }).len()
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u32 = 9;
+    const TITLE: &'static str = "Rope Bridge";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
 }
 
 #[cfg(test)]
@@ -106,7 +225,43 @@ U 20";
 
     #[test]
     pub fn test2() {
-        assert_eq!(part2(&generator(&SAMPLE)), 0);
+        assert_eq!(part2(&generator(&SAMPLE)), 1);
         assert_eq!(part2(&generator(&SAMPLE2)), 36);
     }
+
+    #[test]
+    pub fn test_all_knots() {
+        let mut rope = Rope::new(10, Follow::Chebyshev).with_trail();
+        rope.apply(&generator(&SAMPLE));
+        let counts = rope.visited_counts();
+        assert_eq!((counts[1], counts[9]), (13, 1));
+        assert_eq!(rope.trail().map(|t| t.len()), Some(25));
+        let mut rope = Rope::new(10, Follow::Chebyshev);
+        rope.apply(&generator(SAMPLE));
+        assert_eq!((rope.visited_counts(), rope.trail()), (counts, None));
+    }
+
+    #[test]
+    pub fn test_motions() {
+        let moves = [(RIGHT, 2), (UP, 0), (UP, 1)];
+        let mut motions = Motions::new(Rope::new(2, Follow::Chebyshev).with_trail(), &moves);
+        let history = sim::record(&mut motions, 10);
+        assert_eq!(history.len(), 4);
+        assert!(motions.is_done());
+        assert_eq!(motions.state().knots(), &[[2, 1], [1, 0]]);
+        motions.restore(&history[1]);
+        assert_eq!(motions.state().knots(), &[[1, 0], [0, 0]]);
+        assert_eq!(sim::run(&mut motions, 1), 1);
+        assert_eq!(motions.state().trail().map(|t| t.len()), Some(3));
+    }
+
+    #[test]
+    pub fn test_3d() {
+        let mut rope = Rope::<3>::new(3, Follow::Lagged);
+        rope.apply(&[([1, 0, 0], 3), ([0, 0, 1], 2)]);
+        assert_eq!(rope.knots(), &[[3, 0, 2], [3, 0, 1], [2, 0, 0]]);
+        let mut rope = Rope::<3>::new(2, Follow::Manhattan);
+        rope.apply(&[([1, 0, 0], 1), ([0, 1, 0], 1)]);
+        assert_eq!(rope.knots(), &[[1, 1, 0], [0, 1, 0]]);
+    }
 }
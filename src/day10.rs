@@ -1,6 +1,8 @@
-use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
+use aoc::sim::{self, Simulation};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Op {
     NOOP,
     ADDX(i32),
@@ -21,7 +23,6 @@ fn format_crt(input: Vec<bool>) -> String {
     format!("{}", s)
 }
 
-#[aoc_generator(day10)]
 pub fn generator(input: &str) -> Data {
     input
         .lines()
@@ -36,67 +37,120 @@ pub fn generator(input: &str) -> Data {
         .collect()
 }
 
-#[aoc(day10, part1)]
-pub fn part1(inputs: &Data) -> i32 {
-    let mut cycle = 1;
-    let mut val = 1;
-    let coi = vec![20,60,100,140,180,220];
-    inputs.into_iter().fold_while(Vec::<i32>::new(), |mut acc, op| {
-        match op {
-            Op::NOOP => (),
-            Op::ADDX(x) => {
-                cycle += 1;
-                if coi.iter().any(|&c| c == cycle) {
-                    acc.push(cycle * val);
-                }
-                val += x;
-            }
-        }
-        cycle += 1;
-        if coi.iter().any(|&c| c == cycle) {
-            acc.push(cycle * val);
-        }
-        return if cycle > coi[coi.len()-1] {
-            Done(acc)
-        } else {
-            Continue(acc)
-        }
-    }).into_inner().iter().sum()
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
+
+// one clock cycle per step, the crt draws while the cycle runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cpu<'a> {
+    program: &'a [Op],
+    pc: usize,
+    // addx takes two cycles, its value is added at the end of the second
+    pending: Option<i32>,
+    cycle: usize,
+    x: i32,
+    screen: Vec<bool>,
 }
 
+impl<'a> Cpu<'a> {
+    pub fn new(program: &'a [Op]) -> Cpu<'a> {
+        Cpu { program, pc: 0, pending: None, cycle: 0, x: 1, screen: vec![false; SCREEN_WIDTH * SCREEN_HEIGHT] }
+    }
 
-#[aoc(day10, part2)]
-pub fn part2(inputs: &Data) -> String {
-    let mut cycle: usize = 0;
-    let mut val = 0;
-    let mut sprite: Vec<i32> = vec![1,2,3];
-    let mut screen = inputs.into_iter().fold_while(vec![false; 241], |mut acc, op| {
-        cycle += 1;
-        if sprite.iter().any(|&s| s % 40 == (cycle as i32) % 40) {
-            acc[cycle] = true;
+    // the cycle the next step runs, counting from 1 like the puzzle
+    pub fn next_cycle(&self) -> usize {
+        self.cycle + 1
+    }
+
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    pub fn signal_strength(&self) -> i32 {
+        self.next_cycle() as i32 * self.x
+    }
+
+    pub fn screen(&self) -> String {
+        format_crt(self.screen.clone())
+    }
+}
+
+impl Simulation for Cpu<'_> {
+    type State = Self;
+    type Snapshot = Self;
+
+    fn step(&mut self) {
+        if let Some(pixel) = self.screen.get_mut(self.cycle) {
+            *pixel = (self.x - (self.cycle % SCREEN_WIDTH) as i32).abs() <= 1;
         }
-        match op {
-            Op::NOOP => (),
-            Op::ADDX(x) => {
-                val += x;
-                sprite = vec![val, val+1, val+2];
-                cycle += 1;
-                if sprite.iter().any(|&s| s % 40 == (cycle as i32) % 40) {
-                    acc[cycle] = true;
-                }
+        self.cycle += 1;
+        match (self.pending.take(), self.program.get(self.pc)) {
+            (Some(v), _) => {
+                self.x += v;
+                self.pc += 1;
             }
+            (None, Some(Op::ADDX(v))) => self.pending = Some(*v),
+            (None, Some(Op::NOOP)) => self.pc += 1,
+            (None, None) => {}
         }
-        return if cycle >= 241 {
-            Done(acc)
-        } else {
-            Continue(acc)
+    }
+
+    fn state(&self) -> &Self {
+        self
+    }
+
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: &Self) {
+        *self = snapshot.clone();
+    }
+
+    fn is_done(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+}
+
+pub fn part1(inputs: &Data) -> i32 {
+    let coi = [20, 60, 100, 140, 180, 220];
+    let mut cpu = Cpu::new(inputs);
+    let mut sum = 0;
+    while !cpu.is_done() && cpu.next_cycle() <= coi[coi.len() - 1] {
+        if coi.contains(&cpu.next_cycle()) {
+            sum += cpu.signal_strength();
         }
-    }).into_inner();
-    screen[0] = true;
-    format_crt(screen)
+        cpu.step();
+    }
+    sum
+}
+
+
+pub fn part2(inputs: &Data) -> String {
+    let mut cpu = Cpu::new(inputs);
+    sim::run(&mut cpu, SCREEN_WIDTH * SCREEN_HEIGHT);
+    cpu.screen()
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+    const TITLE: &'static str = "Cathode-Ray Tube";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
 
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -266,4 +320,15 @@ noop";
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), RESULT);
     }
+
+    #[test]
+    pub fn test_cpu() {
+        let program = generator("noop\naddx 3\naddx -5");
+        let mut cpu = Cpu::new(&program);
+        let history = sim::record(&mut cpu, 10);
+        assert_eq!(history.iter().map(|c| c.x()).collect::<Vec<_>>(), vec![1, 1, 1, 4, 4, -1]);
+        assert!(cpu.is_done());
+        cpu.restore(&history[2]);
+        assert_eq!((cpu.next_cycle(), cpu.x()), (3, 1));
+    }
 }
@@ -1,5 +1,8 @@
 use std::collections::VecDeque;
+use std::fmt;
 use itermore::IterSorted;
+use aoc::sim::{self, Simulation};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 #[derive(Debug, PartialOrd, PartialEq, Clone, Copy)]
 pub enum Op {
@@ -37,10 +40,15 @@ impl Monkey {
     }
 }
 
+impl fmt::Display for Monkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "inspected {:>6} times, holding {}", self.inspection_count, self.items.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", "))
+    }
+}
+
 type Data = Vec<Monkey>;
 
 
-#[aoc_generator(day11)]
 pub fn generator(input: &str) -> Data {
     let mut data = Vec::<Monkey>::new();
 
@@ -71,39 +79,103 @@ pub fn generator(input: &str) -> Data {
     data
 }
 
-#[aoc(day11, part1)]
 pub fn part1(inputs: &Data) -> i64 {
     run(inputs, 20, true)
 }
 
 
-#[aoc(day11, part2)]
 pub fn part2(inputs: &Data) -> i64 {
     run(inputs, 10000, false)
 }
 
-pub fn run(inputs: &Data, rounds: usize, div3: bool) -> i64 {
-    let gcd = inputs.iter().map(|m| m.test_div).product::<i64>();
-    let mut monkeys = inputs.clone();
-    for _i in 0..rounds {
+// one round of every monkey taking its turn per step
+#[derive(Debug, Clone, PartialEq)]
+pub struct Troop {
+    monkeys: Vec<Monkey>,
+    // product of all divisors, keeps worry levels small without changing any test
+    modulus: i64,
+    div3: bool,
+    round: usize,
+}
+
+impl Troop {
+    pub fn new(monkeys: &[Monkey], div3: bool) -> Troop {
+        let modulus = monkeys.iter().map(|m| m.test_div).product::<i64>();
+        Troop { monkeys: monkeys.to_vec(), modulus, div3, round: 0 }
+    }
+
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    pub fn inspection_counts(&self) -> Vec<i64> {
+        self.monkeys.iter().map(|m| m.inspection_count).collect()
+    }
+
+    pub fn monkey_business(&self) -> i64 {
+        self.monkeys.iter().map(|m| m.inspection_count).sorted().rev().take(2).product()
+    }
+}
+
+impl Simulation for Troop {
+    type State = [Monkey];
+    type Snapshot = Troop;
+
+    fn step(&mut self) {
+        let monkeys = &mut self.monkeys;
         for n in 0..monkeys.len() {
-            while ! monkeys[n].items.is_empty() {
-                let mut item = monkeys[n].items.pop_front().unwrap();
-                if div3 {
-                    item = (monkeys[n].op.apply(item) / 3) % gcd;
+            while let Some(mut item) = monkeys[n].items.pop_front() {
+                if self.div3 {
+                    item = (monkeys[n].op.apply(item) / 3) % self.modulus;
                 } else {
-                    item = monkeys[n].op.apply(item) % gcd;
+                    item = monkeys[n].op.apply(item) % self.modulus;
                 }
                 let to = monkeys[n].throw_target(item);
                 monkeys[n].inspection_count += 1;
                 monkeys[to].items.push_back(item);
             }
         }
+        self.round += 1;
+    }
+
+    fn state(&self) -> &[Monkey] {
+        &self.monkeys
+    }
+
+    fn snapshot(&self) -> Troop {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: &Troop) {
+        *self = snapshot.clone();
     }
-    monkeys.iter().map(|m| m.inspection_count).sorted().rev().take(2).product()
 }
 
+pub fn run(inputs: &Data, rounds: usize, div3: bool) -> i64 {
+    let mut troop = Troop::new(inputs, div3);
+    sim::run(&mut troop, rounds);
+    troop.monkey_business()
+}
+
+pub struct Day11;
 
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+    const TITLE: &'static str = "Monkey in the Middle";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -147,4 +219,14 @@ Monkey 3:
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 2713310158);
     }
+
+    #[test]
+    pub fn test_troop() {
+        let mut troop = Troop::new(&generator(SAMPLE), false);
+        let history = sim::record(&mut troop, 20);
+        assert_eq!(troop.round(), 20);
+        assert_eq!(troop.inspection_counts(), vec![99, 97, 8, 103]);
+        troop.restore(&history[1]);
+        assert_eq!(troop.inspection_counts(), vec![2, 4, 3, 6]);
+    }
 }
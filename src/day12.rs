@@ -1,71 +1,78 @@
 use aoc::*;
+use crate::solution::{Answer, IntoAnswer, Solution, Variant};
 
-type Data = Vec<Vec<char>>;
+// height map with S and E replaced by their elevation, start and destination
+type Data = (Vec<Vec<char>>, Point, Point);
 
 
-#[aoc_generator(day12)]
 pub fn generator(input: &str) -> Data {
-    parse_grid(input.lines().collect::<Vec<_>>().as_slice())
+    let parsed = GridParser::new().marker('S', 'a').marker('E', 'z').parse_dense(input, |c| c);
+    let (start, dest) = (parsed.marker('S').unwrap(), parsed.marker('E').unwrap());
+    (parsed.grid, start, dest)
 }
 
-#[aoc(day12, part1)]
 pub fn part1(inputs: &Data) -> i64 {
-    let start = inputs.points().find(|p| inputs.get_value(*p).unwrap() == 'S').unwrap();
-    let dest = inputs.points().find(|p| inputs.get_value(*p).unwrap() == 'E').unwrap();
+    let (grid, start, dest) = inputs;
     astar_grid(
-        inputs,
+        grid,
         |_p, _c| true,
         |_p1, c1, _p2, c2| {
-            let height = if *c2 == 'E' { b'z' } else { *c2 as u8 };
-            if *c1 as u8 +1 >= height || *c1 == 'S' {
+            if *c1 as u8 + 1 >= *c2 as u8 {
                 Some(1)
             } else {
                 None
             }
         },
-        start,
-    dest
+        *start,
+        *dest
     ).unwrap().0
 }
 
-#[aoc(day12, part1, bfs)]
 pub fn part1_bfs(inputs: &Data) -> usize {
-    let start = inputs.points().find(|p| inputs.get_value(*p).unwrap() == 'S').unwrap();
-    let dest = inputs.points().find(|p| inputs.get_value(*p).unwrap() == 'E').unwrap();
+    let (grid, start, dest) = inputs;
     bfs_grid(
-        inputs,
-        |_p1, c1, _p2, c2| {
-            let height = if *c2 == 'E' { b'z' } else { *c2 as u8 };
-            if *c1 as u8 +1 >= height || *c1 == 'S' {
-                true
-            } else {
-                false
-            }
-        },
-        start,
-        dest
+        grid,
+        |_p1, c1, _p2, c2| *c1 as u8 + 1 >= *c2 as u8,
+        *start,
+        *dest
     ).unwrap().len()
 }
 
+// the part 1 search as a simulation, one expansion per step
+pub fn replay(inputs: &Data, bfs: bool) -> Replay<Point> {
+    let (grid, start, dest) = inputs;
+    let (path, trace) = if bfs {
+        bfs_grid_traced(grid, |_p1, c1, _p2, c2| *c1 as u8 + 1 >= *c2 as u8, *start, *dest)
+    } else {
+        let (found, trace) = astar_grid_traced(
+            grid,
+            |_p, _c| true,
+            |_p1, c1, _p2, c2| (*c1 as u8 + 1 >= *c2 as u8).then_some(1),
+            *start,
+            *dest
+        );
+        (found.map(|(_cost, path)| path), trace)
+    };
+    Replay::new(trace, path)
+}
+
 
-#[aoc(day12, part2)]
 pub fn part2(inputs: &Data) -> i64 {
-    let starts = inputs.points().filter(|p| inputs.get_value(*p).unwrap() == 'a').collect::<Vec<_>>();
-    let dest = inputs.points().find(|p| inputs.get_value(*p).unwrap() == 'E').unwrap();
+    let (grid, _, dest) = inputs;
+    let starts = grid.points().filter(|p| grid.get_value(*p).unwrap() == 'a').collect::<Vec<_>>();
     starts.iter().filter_map(|start| {
         if let Some(p) = astar_grid(
-            inputs,
+            grid,
             |_p, _c| true,
             |_p1, c1, _p2, c2| {
-                let height = if *c2 == 'E' { b'z' } else { *c2 as u8 };
-                if *c1 as u8 +1 >= height || *c1 == 'S' {
+                if *c1 as u8 + 1 >= *c2 as u8 {
                     Some(1)
                 } else {
                     None
                 }
             },
             *start,
-            dest
+            *dest
         ) {
             Some(p.0)
         } else {
@@ -74,23 +81,15 @@ pub fn part2(inputs: &Data) -> i64 {
     }).min().unwrap()
 }
 
-#[aoc(day12, part2, bfs)]
 pub fn part2_bfs(inputs: &Data) -> usize {
-    let starts = inputs.points().filter(|p| inputs.get_value(*p).unwrap() == 'a').collect::<Vec<_>>();
-    let dest = inputs.points().find(|p| inputs.get_value(*p).unwrap() == 'E').unwrap();
+    let (grid, _, dest) = inputs;
+    let starts = grid.points().filter(|p| grid.get_value(*p).unwrap() == 'a').collect::<Vec<_>>();
     starts.iter().filter_map(|&start| {
         if let Some(p) = bfs_grid(
-            inputs,
-            |_p1, c1, _p2, c2| {
-                let height = if *c2 == 'E' { b'z' } else { *c2 as u8 };
-                if *c1 as u8 +1 >= height || *c1 == 'S' {
-                    true
-                } else {
-                    false
-                }
-            },
+            grid,
+            |_p1, c1, _p2, c2| *c1 as u8 + 1 >= *c2 as u8,
             start,
-            dest
+            *dest
         ) {
             Some(p.len())
         } else {
@@ -99,11 +98,38 @@ pub fn part2_bfs(inputs: &Data) -> usize {
     }).min().unwrap()
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+    const TITLE: &'static str = "Hill Climbing Algorithm";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
 
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+
+    fn variants() -> Vec<Variant<Data>> {
+        vec![
+            Variant { part: 1, name: "bfs", run: |input| part1_bfs(input).into_answer() },
+            Variant { part: 2, name: "bfs", run: |input| part2_bfs(input).into_answer() },
+        ]
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::sim::{self, Simulation};
+    use crate::differential::assert_agree;
 
     const SAMPLE: &str = "Sabqponm
 abcryxxl
@@ -131,4 +157,30 @@ abdefghi";
     pub fn test2_bfs() {
         assert_eq!(part2_bfs(&generator(&SAMPLE)), 29);
     }
+
+    #[test]
+    pub fn test_replay() {
+        let data = generator(SAMPLE);
+        for (bfs, steps) in [(false, 32), (true, 31)] {
+            let mut replay = replay(&data, bfs);
+            assert_eq!(replay.current(), None);
+            assert!(replay.frontier().contains(&data.1));
+            sim::run(&mut replay, 10);
+            assert_eq!(replay.visited().len(), 10);
+            assert_eq!(replay.path(), None);
+            sim::run_to_end(&mut replay);
+            assert_eq!(replay.current(), Some(data.2));
+            assert_eq!(replay.path().unwrap().len(), steps);
+            replay.restore(&10);
+            assert_eq!(replay.steps(), 10);
+            assert_eq!(replay.visited().len(), 10);
+        }
+    }
+
+    #[test]
+    pub fn test_variants() {
+        let entries = Day12::entries();
+        assert_eq!(assert_agree(&entries, 12, 1, SAMPLE), Ok("31".to_string()));
+        assert_eq!(assert_agree(&entries, 12, 2, SAMPLE), Ok("29".to_string()));
+    }
 }
@@ -3,6 +3,7 @@ use std::num::ParseIntError;
 use std::str::FromStr;
 
 use thiserror::Error;
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 #[derive(Clone, Eq, Debug)]
 pub enum Packet {
@@ -104,7 +105,6 @@ pub enum ParseError {
 type Data = Vec<(Packet, Packet)>;
 
 
-#[aoc_generator(day13)]
 pub fn generator(input: &str) -> Data {
     input.lines().collect::<Vec<&str>>().chunks(3).map(|c| {
         match c {
@@ -114,7 +114,6 @@ pub fn generator(input: &str) -> Data {
     }).collect()
 }
 
-#[aoc(day13, part1)]
 pub fn part1(inputs: &Data) -> usize {
     inputs.iter().enumerate().filter_map(|(n, (a, b))| {
         if a <= b {
@@ -126,7 +125,6 @@ pub fn part1(inputs: &Data) -> usize {
 }
 
 
-#[aoc(day13, part2)]
 pub fn part2(inputs: &Data) -> usize {
     let mut all = inputs.into_iter().fold(vec![], |mut acc, (a, b)| {
         acc.push(a);
@@ -146,7 +144,25 @@ pub fn part2(inputs: &Data) -> usize {
     }).product()
 }
 
+pub struct Day13;
 
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+    const TITLE: &'static str = "Distress Signal";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
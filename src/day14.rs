@@ -1,129 +1,239 @@
-use std::collections::HashMap;
+use std::fmt;
 use aoc::*;
+use aoc::sim::{self, Simulation};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Default, Ord, PartialOrd, Eq, PartialEq, Debug)]
 pub enum Structure {
+    #[default]
     Empty,
     Rock,
     Sand
 }
 
 
-type Data = (HashMap::<Point, Structure>, i64);
+type Data = Vec<Point>;
 
-pub const SAND_ORIGIN: Point = [500, 0];
+pub const SAND_ORIGIN: Point = Point::new(500, 0);
 
 fn parse_point(s: &str) -> Point {
-    let (x, y) = s.split_once(",").unwrap();
-    [x.parse().unwrap(), y.parse().unwrap()]
+    let (x, y) = s.split_once(',').unwrap();
+    Point::new(x.parse().unwrap(), y.parse().unwrap())
 }
 
 fn get_next_possibles(p: Point) -> [Point; 3] {
-    return [
-        [p[0], p[1]+1],
-        [p[0]-1, p[1]+1],
-        [p[0]+1, p[1]+1]
+    [
+        Point::new(p.x, p.y+1),
+        Point::new(p.x-1, p.y+1),
+        Point::new(p.x+1, p.y+1)
     ]
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaveConfig {
+    pub sources: Vec<Point>,
+    // floor this many rows below the lowest rock, no floor means sand falls into the abyss
+    pub floor: Option<i64>,
+    // inclusive x range of a chamber with walls on both sides
+    pub chamber: Option<(i64, i64)>,
+}
 
-#[aoc_generator(day14)]
-pub fn generator(input: &str) -> Data {
-    let mut min_y = 0;
-    (input.lines().fold(HashMap::new(), |mut acc, line| {
-        let points: Vec<Point> = line.split(" -> ").map(|part| parse_point(part)).collect();
-        points.windows(2).for_each(|p| {
-            let (x1, x2) = (std::cmp::min(p[0][0], p[1][0]), std::cmp::max(p[0][0], p[1][0]));
-            let (y1, y2) = (std::cmp::min(p[0][1], p[1][1]), std::cmp::max(p[0][1], p[1][1]));
-            for x in x1..=x2 {
-                for y in y1..=y2 {
-                    acc.insert([x, y], Structure::Rock);
-                    if y > min_y {
-                        min_y = y;
-                    }
-                }
+impl Default for CaveConfig {
+    fn default() -> Self {
+        CaveConfig {
+            sources: vec![SAND_ORIGIN],
+            floor: None,
+            chamber: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cave {
+    grid: Vec<Vec<Structure>>,
+    // cave coordinate of grid cell [0, 0]
+    offset: Point,
+    sources: Vec<Point>,
+    // the source that is pouring, the path of its last grain and the grains that came to rest per source
+    source: usize,
+    path: Vec<Point>,
+    counts: Vec<usize>,
+}
+
+impl Cave {
+    pub fn new(rocks: &[Point], config: &CaveConfig) -> Cave {
+        let all = || rocks.iter().chain(config.sources.iter());
+        let min_y = all().map(|p| p.y).min().unwrap_or(0).min(0);
+        let max_rock_y = all().map(|p| p.y).max().unwrap_or(0);
+        let max_y = max_rock_y + config.floor.unwrap_or(0);
+        let (min_x, max_x) = match config.chamber {
+            Some((lo, hi)) => (lo - 1, hi + 1),
+            None => {
+                let spread = if config.floor.is_some() { max_y - min_y + 1 } else { 1 };
+                (
+                    all().map(|p| p.x).min().unwrap_or(0) - spread,
+                    all().map(|p| p.x).max().unwrap_or(0) + spread,
+                )
             }
-        });
-        acc
-    }), min_y + 2)
+        };
+
+        let mut cave = Cave {
+            grid: vec![vec![Structure::Empty; (max_x - min_x + 1) as usize]; (max_y - min_y + 1) as usize],
+            offset: Point::new(min_x, min_y),
+            sources: config.sources.clone(),
+            source: 0,
+            path: config.sources.first().into_iter().copied().collect(),
+            counts: vec![0; config.sources.len()],
+        };
+        for &rock in rocks {
+            cave.set(rock, Structure::Rock);
+        }
+        if config.floor.is_some() {
+            (min_x..=max_x).for_each(|x| cave.set(Point::new(x, max_y), Structure::Rock));
+        }
+        if config.chamber.is_some() {
+            (min_y..=max_y).for_each(|y| {
+                cave.set(Point::new(min_x, y), Structure::Rock);
+                cave.set(Point::new(max_x, y), Structure::Rock);
+            });
+        }
+        cave
+    }
+
+    pub fn get(&self, p: Point) -> Option<Structure> {
+        self.grid.get_value(p - self.offset)
+    }
+
+    fn set(&mut self, p: Point, s: Structure) {
+        self.grid.set_value(p - self.offset, s)
+    }
+
+    pub fn count(&self, s: Structure) -> usize {
+        self.grid.iter().flatten().filter(|&&c| c == s).count()
+    }
+
+    // Pour every source in turn until it is blocked or sand falls into the abyss. The path
+    // of the last grain is kept as a stack, the next grain starts from where it came from.
+    pub fn fill(&mut self) -> Vec<usize> {
+        sim::run_to_end(self);
+        self.counts.clone()
+    }
+
+    fn next_source(&mut self) {
+        self.source += 1;
+        self.path = self.sources.get(self.source).into_iter().copied().collect();
+    }
 }
 
-pub fn print(map: &HashMap<Point, Structure>) {
-    for y in 0..=12 {
-        for x in 485..=515 {
-            if (x, y) == (500, 0) {
-                print!("+");
-            } else {
-                match map.get(&[x, y]) {
-                    Some(Structure::Sand) => print!("o"),
-                    Some(Structure::Rock) => print!("#"),
-                    _ => print!("."),
+// one grain per step, a step that finds the source blocked or sand in the abyss moves on to the next source
+impl Simulation for Cave {
+    type State = Self;
+    type Snapshot = Self;
+
+    fn step(&mut self) {
+        while let Some(&pos) = self.path.last() {
+            if self.get(pos) != Some(Structure::Empty) {
+                break;
+            }
+            let next = get_next_possibles(pos).into_iter().find(|&next| !matches!(self.get(next), Some(Structure::Rock | Structure::Sand)));
+            match next {
+                Some(next) if self.get(next).is_none() => break,
+                Some(next) => self.path.push(next),
+                None => {
+                    self.set(pos, Structure::Sand);
+                    self.counts[self.source] += 1;
+                    self.path.pop();
+                    return;
                 }
             }
         }
-        println!();
+        self.next_source();
+    }
+
+    fn state(&self) -> &Self {
+        self
+    }
+
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: &Self) {
+        *self = snapshot.clone();
+    }
+
+    fn is_done(&self) -> bool {
+        self.source >= self.sources.len()
     }
-    println!();
 }
 
-#[aoc(day14, part1)]
-pub fn part1(inputs: &Data) -> usize {
-    let mut counter = 0;
-    let mut map = inputs.0.clone();
-    let min_y = inputs.1;
-    'outer: loop {
-        let mut pos = SAND_ORIGIN;
-        loop {
-            if let Some(next) = get_next_possibles(pos).iter().find_map(|next| match map.get(next) {
-                Some(Structure::Empty) | None => Some(next),
-                _ => None
-            }) {
-                if next[1] > min_y {
-                    return counter;
-                }
-                pos = *next;
-            } else {
-                map.insert(pos, Structure::Sand);
-                counter += 1;
-                continue 'outer;
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                let p = Point::new(x as i64, y as i64) + self.offset;
+                let c = match c {
+                    _ if self.sources.contains(&p) && *c == Structure::Empty => '+',
+                    Structure::Sand => 'o',
+                    Structure::Rock => '#',
+                    Structure::Empty => '.',
+                };
+                write!(f, "{}", c)?;
             }
+            writeln!(f)?;
         }
+        Ok(())
     }
 }
 
 
-#[aoc(day14, part2)]
-pub fn part2(inputs: &Data) -> usize {
-    let mut counter = 0;
-    let mut map = inputs.0.clone();
-    let min_y = inputs.1;
-    'outer: loop {
-        let mut pos = SAND_ORIGIN;
-        loop {
-            if let Some(next) = get_next_possibles(pos).iter().find_map(|next| match map.get(next) {
-                Some(Structure::Empty) | None => Some(next),
-                _ => None
-            }) {
-                if next[1] == min_y - 1 {
-                    map.insert(*next, Structure::Sand);
-                    counter += 1;
-                    continue 'outer;
-                }
-                pos = *next;
-            } else {
-                if map.get(&[500,0]).unwrap_or(&Structure::Empty) == &Structure::Sand {
-                    return counter;
+pub fn generator(input: &str) -> Data {
+    input.lines().fold(Vec::new(), |mut acc, line| {
+        let points: Vec<Point> = line.split(" -> ").map(parse_point).collect();
+        points.windows(2).for_each(|p| {
+            let (x1, x2) = (std::cmp::min(p[0].x, p[1].x), std::cmp::max(p[0].x, p[1].x));
+            let (y1, y2) = (std::cmp::min(p[0].y, p[1].y), std::cmp::max(p[0].y, p[1].y));
+            for x in x1..=x2 {
+                for y in y1..=y2 {
+                    acc.push(Point::new(x, y));
                 }
-                map.insert(pos, Structure::Sand);
-                counter += 1;
-                continue 'outer;
             }
-        }
-    }
+        });
+        acc
+    })
 }
 
+pub fn part1(inputs: &Data) -> usize {
+    let mut cave = Cave::new(inputs, &CaveConfig::default());
+    cave.fill().iter().sum()
+}
 
 
+pub fn part2(inputs: &Data) -> usize {
+    let mut cave = Cave::new(inputs, &CaveConfig { floor: Some(2), ..Default::default() });
+    cave.fill().iter().sum()
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+    const TITLE: &'static str = "Regolith Reservoir";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,4 +251,25 @@ mod tests {
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 93);
     }
+
+    #[test]
+    pub fn test_chamber() {
+        let config = CaveConfig { sources: vec![Point::new(500, 0), Point::new(502, 0)], floor: Some(2), chamber: Some((499, 502)) };
+        let mut cave = Cave::new(&[], &config);
+        assert_eq!(cave.fill(), vec![4, 2]);
+        assert_eq!(cave.count(Structure::Sand), 6);
+        assert_eq!(cave.to_string(), "#.o.o#\n#oooo#\n######\n");
+    }
+
+    #[test]
+    pub fn test_grains() {
+        let mut cave = Cave::new(&generator(SAMPLE), &CaveConfig::default());
+        assert_eq!(sim::run(&mut cave, 5), 5);
+        assert_eq!(cave.count(Structure::Sand), 5);
+        let snapshot = cave.snapshot();
+        assert_eq!(sim::run_to_end(&mut cave), 20);
+        assert_eq!(cave.count(Structure::Sand), 24);
+        cave.restore(&snapshot);
+        assert_eq!(cave.fill(), vec![24]);
+    }
 }
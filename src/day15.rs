@@ -2,12 +2,12 @@ use std::collections::HashSet;
 use aoc::*;
 use regex::Regex;
 use itertools::Itertools;
+use crate::solution::{Answer, IntoAnswer, Solution, Variant};
 
 type Data = (Vec<(Point, i64)>, Vec<Point>, Vec<Point>);
 
 
 
-#[aoc_generator(day15)]
 pub fn generator(input: &str) -> Data {
     let mut sensors = vec![];
     let mut beacons = vec![];
@@ -15,8 +15,8 @@ pub fn generator(input: &str) -> Data {
     let re = Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)").unwrap();
     for line in input.lines() {
         let c = re.captures(line).expect("No match found");
-        let sensor: Point = [c[1].parse().unwrap(), c[2].parse().unwrap()];
-        let beacon: Point = [c[3].parse().unwrap(), c[4].parse().unwrap()];
+        let sensor = Point::new(c[1].parse().unwrap(), c[2].parse().unwrap());
+        let beacon = Point::new(c[3].parse().unwrap(), c[4].parse().unwrap());
         let dist = manhattan(sensor, beacon);
         sensors.push(sensor);
         beacons.push(beacon);
@@ -25,18 +25,15 @@ pub fn generator(input: &str) -> Data {
     (pos_dist, sensors, beacons)
 }
 
-#[aoc(day15, part1)]
 pub fn part1(inputs: &Data) -> usize {
     solve_part1(inputs, 2000000)
 }
 
-#[aoc(day15, part1, naive)]
 pub fn part1_naive(inputs: &Data) -> usize {
     solve_part1_naive(inputs, 2000000)
 }
 
 
-#[aoc(day15, part2)]
 pub fn part2(inputs: &Data) -> i64 {
     solve_part2(inputs, 4000000)
 }
@@ -44,9 +41,9 @@ pub fn part2(inputs: &Data) -> i64 {
 pub fn solve_part1_naive(inputs: &Data, roi_y: i64) -> usize {
     let (pos_dist, sensors, beacons) = inputs;
     let mut impossibles: HashSet<Point> = HashSet::new();
-    sensors.iter().filter(|p| p[1] == roi_y).for_each(|p| { impossibles.insert(*p); });
+    sensors.iter().filter(|p| p.y == roi_y).for_each(|p| { impossibles.insert(*p); });
     for x in -5000000..=5000000 {
-        let candidate = [x, roi_y];
+        let candidate = Point::new(x, roi_y);
         if beacons.contains(&candidate) {
             continue;
         }
@@ -74,18 +71,43 @@ pub fn solve_part2(inputs: &Data, roi: i64) -> i64 {
     let uncovered = pos_dist
         .iter()
         .flat_map(|(p, d)| manhattan_circumference_plus(p, *d, 1))
-        .filter(|p| p[0] > 0 && p[1] > 0 && p[0] <= roi && p[1] <= roi)
+        .filter(|p| p.x > 0 && p.y > 0 && p.x <= roi && p.y <= roi)
         .filter(|p| !beacons.contains(p))
         .find(|candidate| pos_dist.iter().all(|(p, d)| manhattan(*p, *candidate) > *d))
         .unwrap();
-        uncovered[0] * 4000000 + uncovered[1]
+        uncovered.x * 4000000 + uncovered.y
 }
 
+pub struct Day15;
 
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+    const TITLE: &'static str = "Beacon Exclusion Zone";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+
+    fn variants() -> Vec<Variant<Data>> {
+        vec![
+            Variant { part: 1, name: "naive", run: |input| part1_naive(input).into_answer() },
+        ]
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::differential::assert_agree;
 
     const SAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
@@ -117,4 +139,13 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3";
     pub fn test2() {
         assert_eq!(solve_part2(&generator(&SAMPLE), 20), 56000011);
     }
+
+    #[test]
+    pub fn test_variants() {
+        let entries = Day15::entries();
+        // the registered parts look at row 2000000, move the sample's row 10 there
+        let y = Regex::new(r"y=(-?\d+)").unwrap();
+        let shifted = y.replace_all(SAMPLE, |c: &regex::Captures| format!("y={}", c[1].parse::<i64>().unwrap() + 1999990));
+        assert_eq!(assert_agree(&entries, 15, 1, &shifted), Ok("26".to_string()));
+    }
 }
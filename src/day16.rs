@@ -1,109 +1,199 @@
-use std::collections::HashMap;
-use ndarray::Array3;
-
-
-#[aoc(day16, part1)]
-pub fn part1(input: &str) -> u16 {
-    let mut valves: Vec<(&str, u16, Vec<&str>)> = input.lines().fold(Vec::new(), |mut acc, line| {
-        let (id, rate, _, r) = sscanf::sscanf!(line,
-        "Valve {str} has flow rate={u16}; {str:/tunnels? leads? to valves?/} {str}").unwrap();
-        let routes = r.split(", ").collect::<Vec<_>>();
-        acc.push((id, rate, routes));
-        acc
-    });
-
-    valves.sort_by(|a, b| b.1.cmp(&a.1));
-    // create index map
-    let toidx = valves.iter().enumerate().map(|(i, v)| (v.0, i)).collect::<HashMap<_, _>>();
-    let m = valves.iter().filter(|v| v.1 > 0).count();
-    let n = valves.len();
-    let mut adj = vec![vec![0usize; 0]; n];
-    let mut flow = vec![0u16; n];
-    for v in valves.iter() {
-        let i = toidx[v.0];
-        flow[i] = v.1;
-        for to in v.2.iter() {
-            adj[i].push(toidx[to])
-        }
-    }
-    let start = toidx["AA"];
-    let mm = 1 << m;
-    // m = valves with positive flow
-    // [time left, current node, bitset of available valves]
-    let mut opt = Array3::<u16>::zeros([30, n, mm]);
-    for t in 1..30 {
-        for i in 0..n {
-            let ii = 1 << i;
-            for x in 0..mm {
-                let mut o  = opt[(t, i, x)];
-                if ii & x != 0 && t >= 2 {
-                    o = o.max(opt[(t - 1, i, x - ii)] + flow[i] * t as u16);
-                }
-                for &j in adj[i].iter() {
-                    o = o.max(opt[(t - 1 , j, x)]);
+use std::collections::{HashMap, VecDeque};
+use crate::cancel;
+use crate::solution::{Answer, IntoAnswer, Solution};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub valve: usize,
+    pub arrive: u32,
+    pub open: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub pressure: u32,
+    pub agents: Vec<Vec<Step>>,
+}
+
+pub struct ValveNetwork {
+    names: Vec<String>,
+    flow: Vec<u32>,
+    // distances between the positive-flow valves, the start valve is the last row/column
+    dist: Vec<Vec<u32>>,
+}
+
+impl ValveNetwork {
+    pub fn new(input: &str, start: &str) -> ValveNetwork {
+        let valves: Vec<(&str, u32, Vec<&str>)> = input.lines().map(|line| {
+            let (id, rate, _, r) = sscanf::sscanf!(line,
+            "Valve {str} has flow rate={u32}; {str:/tunnels? leads? to valves?/} {str}").unwrap();
+            (id, rate, r.split(", ").collect())
+        }).collect();
+        let toidx = valves.iter().enumerate().map(|(i, v)| (v.0, i)).collect::<HashMap<_, _>>();
+        let adj = valves.iter().map(|v| v.2.iter().map(|to| toidx[to]).collect::<Vec<_>>()).collect::<Vec<_>>();
+
+        let mut relevant = (0..valves.len()).filter(|&i| valves[i].1 > 0).collect::<Vec<_>>();
+        relevant.push(toidx[start]);
+
+        let dist = relevant.iter().map(|&from| {
+            let mut d = vec![u32::MAX; valves.len()];
+            let mut q = VecDeque::from([from]);
+            d[from] = 0;
+            while let Some(cur) = q.pop_front() {
+                for &nb in &adj[cur] {
+                    if d[nb] == u32::MAX {
+                        d[nb] = d[cur] + 1;
+                        q.push_back(nb);
+                    }
                 }
-                opt[(t, i , x)] = o;
             }
+            relevant.iter().map(|&to| d[to]).collect()
+        }).collect();
+
+        ValveNetwork {
+            names: relevant.iter().map(|&i| valves[i].0.to_string()).collect(),
+            flow: relevant.iter().map(|&i| valves[i].1).collect(),
+            dist,
         }
     }
-    opt[(29, start, mm -1 )]
-}
 
+    pub fn name(&self, valve: usize) -> &str {
+        &self.names[valve]
+    }
+
+    fn start(&self) -> usize {
+        self.flow.len() - 1
+    }
+
+    fn flow_valves(&self) -> usize {
+        self.flow.len() - 1
+    }
 
-#[aoc(day16, part2)]
-pub fn part2(input: &str) -> u16 {
-    let mut valves: Vec<(&str, u16, Vec<&str>)> = input.lines().fold(Vec::new(), |mut acc, line| {
-        let (id, rate, _, r) = sscanf::sscanf!(line,
-        "Valve {str} has flow rate={u16}; {str:/tunnels? leads? to valves?/} {str}").unwrap();
-        let routes = r.split(", ").collect::<Vec<_>>();
-        acc.push((id, rate, routes));
-        acc
-    });
-
-    valves.sort_by(|a, b| b.1.cmp(&a.1));
-    // create index map
-    let toidx = valves.iter().enumerate().map(|(i, v)| (v.0, i)).collect::<HashMap<_, _>>();
-    let m = valves.iter().filter(|v| v.1 > 0).count();
-    let n = valves.len();
-    let mut adj = vec![vec![0usize; 0]; n];
-    let mut flow = vec![0u16; n];
-    for v in valves.iter() {
-        let i = toidx[v.0];
-        flow[i] = v.1;
-        for to in v.2.iter() {
-            adj[i].push(toidx[to])
+    // best single agent route for every exact set of opened valves
+    fn best_routes(&self, time: u32) -> Vec<Option<(u32, Vec<Step>)>> {
+        let mut best = vec![None; 1 << self.flow_valves()];
+        let mut path = vec![];
+        self.visit(self.start(), time, time, 0, 0, &mut path, &mut best);
+        best
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit(
+        &self,
+        current: usize,
+        budget: u32,
+        remaining: u32,
+        opened: usize,
+        pressure: u32,
+        path: &mut Vec<Step>,
+        best: &mut [Option<(u32, Vec<Step>)>],
+    ) {
+        cancel::checkpoint();
+        if best[opened].as_ref().map(|(p, _)| pressure > *p).unwrap_or(true) {
+            best[opened] = Some((pressure, path.clone()));
+        }
+        for next in 0..self.flow_valves() {
+            let cost = self.dist[current][next].saturating_add(1);
+            if opened & (1 << next) != 0 || cost >= remaining {
+                continue;
+            }
+            let left = remaining - cost;
+            path.push(Step { valve: next, arrive: budget - left - 1, open: budget - left });
+            self.visit(next, budget, left, opened | (1 << next), pressure + self.flow[next] * left, path, best);
+            path.pop();
         }
     }
-    let start = toidx["AA"];
-    let mm = 1 << m;
-    // m = valves with positive flow
-    // [time left, current node, bitset of available valves]
-    let mut opt = Array3::<u16>::zeros([30, n, mm]);
-    for t in 1..30 {
-        for i in 0..n {
-            let ii = 1 << i;
-            for x in 0..mm {
-                let mut o  = opt[(t, i, x)];
-                if ii & x != 0 && t >= 2 {
-                    o = o.max(opt[(t - 1, i, x - ii)] + flow[i] * t as u16);
+
+    pub fn plan(&self, agents: usize, time: u32) -> Plan {
+        let best = self.best_routes(time);
+        let full = best.len() - 1;
+
+        // best_sub[mask] = best exact route that only opens valves in mask
+        let mut best_sub = best.iter().enumerate().map(|(mask, b)| {
+            (b.as_ref().map(|(p, _)| *p).unwrap_or(0), mask)
+        }).collect::<Vec<_>>();
+        for mask in 0..best_sub.len() {
+            for bit in (0..self.flow_valves()).filter(|b| mask & (1 << b) != 0) {
+                let candidate = best_sub[mask ^ (1 << bit)];
+                if candidate.0 > best_sub[mask].0 {
+                    best_sub[mask] = candidate;
                 }
-                for &j in adj[i].iter() {
-                    o = o.max(opt[(t - 1 , j, x)]);
+            }
+        }
+
+        // level[j][mask] = (pressure, route chosen for agent j) when j+1 agents split mask
+        let mut levels = vec![best_sub.clone()];
+        for j in 1..agents {
+            let prev = &levels[j - 1];
+            let masks = if j + 1 == agents { full..=full } else { 0..=full };
+            let mut level = vec![(0, 0); full + 1];
+            for mask in masks {
+                cancel::checkpoint();
+                let mut sub = mask;
+                loop {
+                    let (p, route) = best_sub[sub];
+                    let total = p + prev[mask ^ sub].0;
+                    if total > level[mask].0 {
+                        level[mask] = (total, route);
+                    }
+                    if sub == 0 {
+                        break;
+                    }
+                    sub = (sub - 1) & mask;
                 }
-                opt[(t, i , x)] = o;
             }
+            levels.push(level);
+        }
+
+        let mut schedules = vec![];
+        let mut mask = full;
+        for level in levels.iter().rev() {
+            let route = level[mask].1;
+            schedules.push(best[route].as_ref().map(|(_, s)| s.clone()).unwrap_or_default());
+            mask ^= route;
+        }
+
+        Plan {
+            pressure: levels.last().map(|l| l[full].0).unwrap_or(0),
+            agents: schedules,
         }
     }
-    let mut best = 0;
-    for x in 0..mm / 2 {
-        let y = mm - 1 - x;
-        best = best.max(opt[(25, start, x)] + opt[(25, start, y)])
-    }
-    best
 }
 
 
+pub fn generator(input: &str) -> ValveNetwork {
+    ValveNetwork::new(input, "AA")
+}
+
+pub fn part1(inputs: &ValveNetwork) -> u32 {
+    inputs.plan(1, 30).pressure
+}
+
 
+pub fn part2(inputs: &ValveNetwork) -> u32 {
+    inputs.plan(2, 26).pressure
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+    const TITLE: &'static str = "Proboscidea Volcanium";
+    type Input = ValveNetwork;
+
+    fn parse(input: &str) -> Result<ValveNetwork, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &ValveNetwork) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &ValveNetwork) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -123,12 +213,21 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
 
     #[test]
     pub fn test1() {
-        assert_eq!(part1(&SAMPLE), 1651);
+        assert_eq!(part1(&generator(&SAMPLE)), 1651);
     }
 
 
     #[test]
     pub fn test2() {
-        assert_eq!(part2(&SAMPLE), 1707);
+        assert_eq!(part2(&generator(&SAMPLE)), 1707);
+    }
+
+    #[test]
+    pub fn test_plan() {
+        let network = generator(&SAMPLE);
+        let plan = network.plan(1, 30);
+        let opened = plan.agents[0].iter().map(|s| (network.name(s.valve), s.open)).collect::<Vec<_>>();
+        assert_eq!(opened, vec![("DD", 2), ("BB", 5), ("JJ", 9), ("HH", 17), ("EE", 21), ("CC", 24)]);
+        assert_eq!(network.plan(3, 26).agents.len(), 3);
     }
 }
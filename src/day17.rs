@@ -1,172 +1,168 @@
-use std::collections::{HashSet, VecDeque};
-use nalgebra::min;
+use std::fmt;
 use aoc::*;
-use crate::day17::Shape::*;
-use crate::day17::Jet::*;
+use aoc::sim::{self, Simulation};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 const ROUNDS: usize = 2022;
-const CHAMBER_WIDTH: i64 = 7;
-
-type Data = Vec<Jet>;
-
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
-pub enum Jet {
-    Left,
-    Right,
+const ROUNDS_PART2: usize = 1_000_000_000_000;
+// rows below the top that decide where the next rocks can fall, deep enough for real inputs
+const SURFACE_DEPTH: usize = 32;
+const CHAMBER_WIDTH: usize = 7;
+
+// jets only ever push east or west
+type Data = Vec<Dir4>;
+
+// rows from the bottom up, bit c is column c
+const ROCKS: [&[u8]; 5] = [
+    &[0b1111],
+    &[0b010, 0b111, 0b010],
+    &[0b111, 0b100, 0b100],
+    &[0b1, 0b1, 0b1, 0b1],
+    &[0b11, 0b11],
+];
+
+// one rock falls per step until it comes to rest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chamber<'a> {
+    jets: &'a [Dir4],
+    jet: usize,
+    rocks: usize,
+    rows: Vec<u8>,
 }
 
-impl Jet {
-    pub fn to_offset(&self) -> Point {
-        use Jet::*;
-        match self {
-            Left => LEFT,
-            Right => RIGHT,
-        }
+impl<'a> Chamber<'a> {
+    pub fn new(jets: &'a [Dir4]) -> Chamber<'a> {
+        Chamber { jets, jet: 0, rocks: 0, rows: vec![] }
     }
-}
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone, Copy)]
-pub enum Shape {
-    Minus,
-    Plus,
-    L,
-    Bar,
-    Cluster,
-    Border,
-}
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
 
-impl Shape {
-    pub fn initial_position_x(&self) -> Point {
-        use Shape::*;
-        match self {
-            Minus => [2, -3],
-            Plus => [3, -3],
-            L => [2, -3],
-            Bar => [2, -3],
-            Cluster => [2, -3],
-            Border => [0, 0],
-        }
+    pub fn rocks(&self) -> usize {
+        self.rocks
     }
 
-    pub fn occupied(&self, corner: &Point) -> Vec<Point> {
-        match self {
-            Minus => (0..4).into_iter().map(|xo| point_add(*corner, point_mul(RIGHT, xo))).collect(),
-            Plus => {
-                let mut v = Vec::with_capacity(5);
-                v.push(*corner);
-                v.push(point_add(*corner, UP));
-                v.push(point_add(point_add(*corner, UP), LEFT));
-                v.push(point_add(point_add(*corner, UP), RIGHT));
-                v.push(point_add(point_add(*corner, UP), UP));
-                v
-            },
-            L => {
-                let mut v = Vec::with_capacity(5);
-                v.push(*corner);
-                v.push(point_add(*corner, RIGHT));
-                let p = point_add(point_add(*corner, RIGHT), RIGHT);
-                v.push(p);
-                v.push(point_add(p, UP));
-                v.push(point_add(point_add(p, UP), UP));
-                v
-            },
-            Bar => (0..4).into_iter().map(|yo| point_add(*corner, point_mul(UP, yo))).collect(),
-            Cluster => {
-                let mut v = Vec::with_capacity(4);
-                v.push(*corner);
-                v.push(point_add(*corner, RIGHT));
-                v.push(point_add(*corner, UP));
-                v.push(point_add(point_add(*corner, UP), RIGHT));
-                v
-            },
-            Border => (0..7).into_iter().map(|xo| point_add(*corner, point_mul(RIGHT, xo))).collect(),
-        }
+    // the top rows, together with the next rock and jet they decide everything that follows
+    pub fn surface(&self, depth: usize) -> Vec<u8> {
+        self.rows.iter().rev().take(depth).copied().collect()
     }
-}
 
-const SHAPE_ORDER: [Shape; 5] = [Shape::Minus, Shape::Plus, Shape::L, Shape::Bar, Shape::Cluster];
+    pub fn cycle_key(&self) -> (usize, usize, Vec<u8>) {
+        (self.rocks % ROCKS.len(), self.jet, self.surface(SURFACE_DEPTH))
+    }
 
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter().enumerate().any(|(i, r)| self.rows.get(y + i).is_some_and(|row| row & r != 0))
+    }
 
-#[aoc_generator(day17)]
-pub fn generator(input: &str) -> Data {
-    input.trim().chars().map(|c| {
-        match c {
-            '<' => Jet::Left,
-            '>' => Jet::Right,
-            _ => unreachable!(),
+    fn push(&self, rock: &[u8], jet: Dir4) -> Option<Vec<u8>> {
+        let wall = match jet {
+            Dir4::East => 1 << (CHAMBER_WIDTH - 1),
+            Dir4::West => 1,
+            _ => return None,
+        };
+        if rock.iter().any(|r| r & wall != 0) {
+            return None;
         }
-    }).collect()
+        Some(rock.iter().map(|r| if jet == Dir4::East { r << 1 } else { r >> 1 }).collect())
+    }
 }
 
-#[aoc(day17, part1)]
-pub fn part1(inputs: &Data) -> i64 {
-    let mut shapes = SHAPE_ORDER.iter().cycle();
-    let mut jets = inputs.clone().iter().cycle();
-    let mut miny = 0;
-    let mut height = 0;
-    let mut occupied = HashSet::<Point>::new();
-    for p in Border.occupied(&Border.initial_position_x()).iter() {
-        occupied.insert(*p);
-    }
-
-    let collides = |p: Point, s: &Shape, g: &HashSet<Point>| {
-        s.occupied(&p).iter().any(|p|  p[0] < 0 || p[0] >= CHAMBER_WIDTH || g.contains(p))
-    };
-
-    for i in 0..ROUNDS {
-        let shape = shapes.next().unwrap();
-        //println!("===== {}: {:?} ===== {}", i, shape, miny);
-        let mut p = [shape.initial_position_x()[0], miny - 1 + shape.initial_position_x()[1]];
-        //println!("{:?}", p);
+impl Simulation for Chamber<'_> {
+    type State = Self;
+    type Snapshot = Self;
+
+    fn step(&mut self) {
+        // two units from the left wall, three above the highest rock
+        let mut rock = ROCKS[self.rocks % ROCKS.len()].iter().map(|r| r << 2).collect::<Vec<u8>>();
+        let mut y = self.rows.len() + 3;
         loop {
-            let j = jets.next().unwrap();
-            let new_p = match j {
-                Left => [p[0] - 1, p[1]],
-                Right => [p[0] + 1, p[1]],
-            };
-            if !collides(new_p, shape, &occupied) {
-                p = new_p;
-                //println!("Jet: {:?}, {:?}", p, j);
-            } else {
-                //println!("Jet: No Move, {:?}", j);
+            let jet = self.jets[self.jet];
+            self.jet = (self.jet + 1) % self.jets.len();
+            if let Some(pushed) = self.push(&rock, jet).filter(|p| !self.collides(p, y)) {
+                rock = pushed;
             }
-
-            let new_p = [p[0], p[1] + 1];
-            height += 1;
-            if !collides(new_p, shape, &occupied) {
-                p = new_p;
-                //println!("Down: {:?}", p);
-            } else {
-                //println!("Rest: {:?}", p);
-                for x in shape.occupied(&p).iter() {
-                    //print!("{:?} || ", x);
-                    if x[1] < miny {
-                        miny = x[1];
-                    }
-                    occupied.insert(*x);
-                }
-                //println!();
+            if y == 0 || self.collides(&rock, y - 1) {
                 break;
             }
+            y -= 1;
         }
+        if self.rows.len() < y + rock.len() {
+            self.rows.resize(y + rock.len(), 0);
+        }
+        rock.iter().enumerate().for_each(|(i, r)| self.rows[y + i] |= r);
+        self.rocks += 1;
+    }
+
+    fn state(&self) -> &Self {
+        self
+    }
+
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
 
+    fn restore(&mut self, snapshot: &Self) {
+        *self = snapshot.clone();
     }
 
-    -miny
+    fn is_done(&self) -> bool {
+        self.jets.is_empty()
+    }
+}
+
+impl fmt::Display for Chamber<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows.iter().rev() {
+            let cells = (0..CHAMBER_WIDTH).map(|c| if row & (1 << c) != 0 { '#' } else { '.' }).collect::<String>();
+            writeln!(f, "|{}|", cells)?;
+        }
+        writeln!(f, "+{}+", "-".repeat(CHAMBER_WIDTH))
+    }
+}
+
+
+pub fn generator(input: &str) -> Data {
+    input.trim().chars().map(|c| Dir4::from_char(c).unwrap()).collect()
+}
+
+pub fn part1(inputs: &Data) -> i64 {
+    let mut chamber = Chamber::new(inputs);
+    sim::run(&mut chamber, ROUNDS);
+    chamber.height() as i64
 }
 
 
-#[aoc(day17, part2)]
 pub fn part2(inputs: &Data) -> i64 {
-    0
+    let mut chamber = Chamber::new(inputs);
+    sim::run_extrapolated(&mut chamber, ROUNDS_PART2, Chamber::cycle_key, |c| c.height() as i64)
 }
 
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+    const TITLE: &'static str = "Pyroclastic Flow";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
 
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
 
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::cycle;
 
     const SAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
 
@@ -180,4 +176,50 @@ mod tests {
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 1514285714288);
     }
+
+    #[test]
+    pub fn test_chamber() {
+        let jets = generator(SAMPLE);
+        let mut chamber = Chamber::new(&jets);
+        sim::run(&mut chamber, 3);
+        assert_eq!(chamber.to_string(), "|..#....|\n|..#....|\n|####...|\n|..###..|\n|...#...|\n|..####.|\n+-------+\n");
+        assert_eq!((chamber.height(), chamber.rocks()), (6, 3));
+    }
+
+    #[test]
+    pub fn test_snapshot() {
+        let jets = generator(SAMPLE);
+        let mut chamber = Chamber::new(&jets);
+        sim::run(&mut chamber, 5);
+        let (snapshot, height) = (chamber.snapshot(), chamber.height());
+        sim::run(&mut chamber, 5);
+        assert!(chamber.height() > height);
+        chamber.restore(&snapshot);
+        assert_eq!((chamber.height(), chamber.rocks()), (height, 5));
+    }
+
+    #[test]
+    pub fn test_cycle() {
+        let jets = generator(SAMPLE);
+        let mut chamber = Chamber::new(&jets);
+        let found = sim::find_cycle(&mut chamber, None, Chamber::cycle_key).unwrap();
+        assert_eq!(found.period, 35);
+        // the plain detectors agree on the sequence of keys
+        let mut keys = Chamber::new(&jets);
+        let keys = sim::record(&mut keys, found.start + found.period).iter().map(Chamber::cycle_key).collect::<Vec<_>>();
+        let next = |i: usize| if i + 1 < keys.len() { i + 1 } else { found.start };
+        let same = |i: usize| keys.iter().position(|k| *k == keys[i]).unwrap();
+        assert_eq!(cycle::floyd(0, |i| same(next(i))), found);
+        assert_eq!(cycle::brent(0, |i| same(next(i))), found);
+        let states = std::iter::successors(Some(Chamber::new(&jets)), |c| {
+            let mut c = c.clone();
+            c.step();
+            Some(c)
+        });
+        let height = |c: &Chamber| c.height() as i64;
+        assert_eq!(cycle::extrapolate(states.clone(), ROUNDS_PART2, Chamber::cycle_key, height), Some(1514285714288));
+        assert_eq!(cycle::extrapolate(states.clone().take(10), 20, Chamber::cycle_key, height), None);
+        let heights = states.take(found.start + found.period + 1).map(|c| height(&c)).collect::<Vec<_>>();
+        assert_eq!(cycle::project(found, &heights, ROUNDS), 3068);
+    }
 }
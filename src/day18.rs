@@ -1,13 +1,13 @@
-use std::collections::HashSet;
 use aoc::*;
 use regex::Regex;
-use itertools::Itertools;
+use crate::solution::{Answer, Solution};
+
+const UNSOLVED: &str = "not solved yet";
 
 type Data = (Vec<(Point, i64)>, Vec<Point>, Vec<Point>);
 
 
 
-#[aoc_generator(day18)]
 pub fn generator(input: &str) -> Data {
     let mut sensors = vec![];
     let mut beacons = vec![];
@@ -15,8 +15,8 @@ pub fn generator(input: &str) -> Data {
     let re = Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)").unwrap();
     for line in input.lines() {
         let c = re.captures(line).expect("No match found");
-        let sensor: Point = [c[1].parse().unwrap(), c[2].parse().unwrap()];
-        let beacon: Point = [c[3].parse().unwrap(), c[4].parse().unwrap()];
+        let sensor = Point::new(c[1].parse().unwrap(), c[2].parse().unwrap());
+        let beacon = Point::new(c[3].parse().unwrap(), c[4].parse().unwrap());
         let dist = manhattan(sensor, beacon);
         sensors.push(sensor);
         beacons.push(beacon);
@@ -25,19 +25,36 @@ pub fn generator(input: &str) -> Data {
     (pos_dist, sensors, beacons)
 }
 
-#[aoc(day18, part1)]
 pub fn part1(inputs: &Data) -> usize {
     0
 }
 
 
-#[aoc(day18, part2)]
 pub fn part2(inputs: &Data) -> i64 {
     0
 }
 
+pub struct Day18;
+
+// the parser and parts above are still a copy of day15, so neither part reports an answer and
+// the puzzle input isn't even parsed
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+    const TITLE: &'static str = "Boiling Boulders";
+    type Input = ();
 
+    fn parse(_: &str) -> Result<(), String> {
+        Ok(())
+    }
 
+    fn part1(_: &()) -> Answer {
+        Err(UNSOLVED.to_string())
+    }
+
+    fn part2(_: &()) -> Answer {
+        Err(UNSOLVED.to_string())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -2,6 +2,8 @@ use itertools::Itertools;
 use sscanf::sscanf;
 use crate::day19::Material::{Clay, Geode, Obsidian, Ore};
 use rayon::prelude::*;
+use crate::cancel;
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 
 #[derive(Copy, Clone)]
@@ -49,7 +51,6 @@ impl State {
 
 type Data = Vec<Blueprint>;
 
-#[aoc_generator(day19)]
 pub fn generator(input: &str) -> Data {
     input.lines().map(|line| {
         let (id, ore_ore, clay_ore, obs_ore, obs_clay, geo_ore, geo_obs) = sscanf!(
@@ -79,6 +80,7 @@ pub fn run_blueprint_production(
     prev_skipped: Option<&Vec<usize>>,
     best_so_far: u32
 ) -> u32 {
+    cancel::checkpoint();
     if state.time_remaining == 1 {
         return state.materials[Geode as usize] + state.robots[Geode as usize];
     }
@@ -135,18 +137,37 @@ pub fn max_mat(bp: &Blueprint) -> [u32; 4] {
     max
 }
 
-#[aoc(day19, part1)]
+// the rayon workers don't know the runner's token, so it travels with the closures
 pub fn part1(inputs: &Data) -> u32 {
-    inputs.par_iter().map(|bp| bp.id * get_blueprint_score(bp, 24)).sum()
+    let cancel = cancel::current();
+    inputs.par_iter().map(|bp| bp.id * cancel.install(|| get_blueprint_score(bp, 24))).sum()
 }
 
 
-#[aoc(day19, part2)]
 pub fn part2(inputs: &Data) -> u32 {
-    inputs.par_iter().take(3).map(|bp| get_blueprint_score(bp, 32)).product()
+    let cancel = cancel::current();
+    inputs.par_iter().take(3).map(|bp| cancel.install(|| get_blueprint_score(bp, 32))).product()
 }
 
+pub struct Day19;
 
+impl Solution for Day19 {
+    const DAY: u32 = 19;
+    const TITLE: &'static str = "Not Enough Minerals";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
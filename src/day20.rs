@@ -1,49 +1,107 @@
-use std::collections::{VecDeque};
+use aoc::seq::SqrtList;
+use aoc::sim::{self, Simulation};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 
 
 type Data = Vec<i64>;
 
-const DECRYPTION_KEY: i64 = 811589153;
+pub const DECRYPTION_KEY: i64 = 811589153;
+const GROVE_OFFSETS: [usize; 3] = [1000, 2000, 3000];
 
 
-#[aoc_generator(day20)]
 pub fn generator(input: &str) -> Data {
     input.lines().map(|line| line.parse().unwrap()).collect()
 }
 
-pub fn decrypt(data: &Data, rounds: usize, key: i64) -> i64 {
-    let len = data.len();
-    let mut indicies = (0..len).collect::<VecDeque<_>>();
-    for _round in 0..rounds {
-        for i in 0..len {
-            let p = indicies.iter().position(|&p| p == i).unwrap();
-            let num = data[i] * key;
-            indicies.remove(p);
-            let new_index = (p as i64 + num).rem_euclid(len as i64 - 1);
-            indicies.insert(new_index as usize, i);
-        }
+// moves one number per step, a round is done after every number moved once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mixer<'a> {
+    data: &'a [i64],
+    key: i64,
+    rounds: usize,
+    list: SqrtList,
+    moved: usize,
+}
+
+impl<'a> Mixer<'a> {
+    pub fn new(data: &'a [i64], rounds: usize, key: i64) -> Mixer<'a> {
+        Mixer { data, key, rounds, list: SqrtList::new(data.len()), moved: 0 }
     }
 
-    let numbers = indicies.iter().map(|&i| data[i]).collect::<Vec<_>>();
-    let zero = numbers.iter().position(|&n| n == 0).unwrap();
+    pub fn numbers(&self) -> Vec<i64> {
+        self.list.iter().map(|i| self.data[i] * self.key).collect()
+    }
+}
+
+impl Simulation for Mixer<'_> {
+    type State = SqrtList;
+    type Snapshot = (SqrtList, usize);
+
+    fn step(&mut self) {
+        let i = self.moved % self.data.len();
+        self.list.move_by(i, self.data[i] * self.key);
+        self.moved += 1;
+    }
 
-    [1000,2000,3000].iter().map(|i| numbers[(zero + i) % len] * key).sum()
+    fn state(&self) -> &SqrtList {
+        &self.list
+    }
+
+    fn snapshot(&self) -> Self::Snapshot {
+        (self.list.clone(), self.moved)
+    }
+
+    fn restore(&mut self, (list, moved): &Self::Snapshot) {
+        (self.list, self.moved) = (list.clone(), *moved);
+    }
+
+    fn is_done(&self) -> bool {
+        self.moved >= self.rounds * self.data.len()
+    }
+}
+
+pub fn mix(data: &Data, rounds: usize, key: i64) -> Vec<i64> {
+    let mut mixer = Mixer::new(data, rounds, key);
+    sim::run_to_end(&mut mixer);
+    mixer.numbers()
+}
+
+pub fn decrypt(data: &Data, rounds: usize, key: i64, offsets: &[usize]) -> i64 {
+    let numbers = mix(data, rounds, key);
+    let zero = numbers.iter().position(|&n| n == 0).unwrap();
+    offsets.iter().map(|i| numbers[(zero + i) % numbers.len()]).sum()
 }
 
 
-#[aoc(day20, part1)]
 pub fn part1(inputs: &Data) -> i64 {
-    decrypt(inputs, 1, 1)
+    decrypt(inputs, 1, 1, &GROVE_OFFSETS)
 }
 
 
-#[aoc(day20, part2)]
 pub fn part2(inputs: &Data) -> i64 {
-    decrypt(inputs, 10, DECRYPTION_KEY)
+    decrypt(inputs, 10, DECRYPTION_KEY, &GROVE_OFFSETS)
 }
 
+pub struct Day20;
 
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+    const TITLE: &'static str = "Grove Positioning System";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -67,4 +125,35 @@ mod tests {
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 1623178306);
     }
+
+    #[test]
+    pub fn test_duplicates() {
+        let data = vec![3, 1, -2, 3, 0, 1, -2, 7, 3];
+        let mut naive = (0..data.len()).collect::<std::collections::VecDeque<_>>();
+        for _round in 0..3 {
+            for (i, n) in data.iter().enumerate() {
+                let p = naive.iter().position(|&p| p == i).unwrap();
+                naive.remove(p);
+                naive.insert((p as i64 + n).rem_euclid(data.len() as i64 - 1) as usize, i);
+            }
+        }
+        let expected = naive.iter().map(|&i| data[i]).collect::<Vec<_>>();
+        assert_eq!(mix(&data, 3, 1), expected);
+    }
+
+    #[test]
+    pub fn test_mixer() {
+        let from_zero = |mut v: Vec<i64>| {
+            let zero = v.iter().position(|&n| n == 0).unwrap();
+            v.rotate_left(zero);
+            v
+        };
+        let data = generator(SAMPLE);
+        let mut mixer = Mixer::new(&data, 1, 1);
+        let history = sim::record(&mut mixer, 10);
+        assert_eq!(history.len(), 8);
+        assert_eq!(from_zero(mixer.numbers()), vec![0, 3, -2, 1, 2, -3, 4]);
+        mixer.restore(&history[2]);
+        assert_eq!(from_zero(mixer.numbers()), vec![0, 4, 1, -3, 2, 3, -2]);
+    }
 }
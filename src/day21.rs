@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use crate::day21::MonkeyAction::{Minus, Plus, Mul, Div, Val, Eq};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum MonkeyAction {
@@ -15,7 +16,6 @@ pub enum MonkeyAction {
 type Data = HashMap<i64, MonkeyAction>;
 
 
-#[aoc_generator(day21)]
 pub fn generator(input: &str) -> Data {
     input.lines().fold(HashMap::new(), |mut acc, line| {
         let chars = line.chars().collect::<Vec<_>>();
@@ -103,14 +103,12 @@ pub fn solve2(map: &HashMap<i64, MonkeyAction>, humn: &i64, current: &i64, targe
 
 
 
-#[aoc(day21, part1)]
 pub fn part1(inputs: &Data) -> i64 {
     let root = ['r', 'o', 'o', 't'].iter().fold(0, |acc, &c| acc * 256 + (c as i64 - 'a' as i64));
     inputs.get(&root).unwrap().eval(inputs)
 }
 
 
-#[aoc(day21, part2)]
 pub fn part2(inputs: &Data) -> i64 {
     let mut inputs = inputs.clone();
     let root = ['r', 'o', 'o', 't'].iter().fold(0, |acc, &c| acc * 256 + (c as i64 - 'a' as i64));
@@ -132,7 +130,25 @@ pub fn part2(inputs: &Data) -> i64 {
     solve2(&inputs, &humn, &to_solve, &target)
 }
 
+pub struct Day21;
 
+impl Solution for Day21 {
+    const DAY: u32 = 21;
+    const TITLE: &'static str = "Monkey Math";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -1,5 +1,5 @@
-use std::collections::HashMap;
 use aoc::*;
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 
 pub enum Pieces {
@@ -14,128 +14,67 @@ pub enum Instruction {
     R,
 }
 
-#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
-pub enum Direction {
-    Right = 0,
-    Down = 1,
-    Left = 2,
-    Up = 3,
-}
-
-impl Direction {
-    pub fn turn(self, i: &Instruction) -> Self {
-        use Instruction::*;
-        match i {
-            L => self.left(),
-            R => self.right(),
-            _ => self,
-        }
-    }
-
-    fn left(&self) -> Self  {
-        use Direction::*;
-        match self {
-            Right => Up,
-            Down => Right,
-            Left => Down,
-            Up => Left,
-        }
-    }
-
-    fn right(self) -> Self {
-        use Direction::*;
-        match self {
-            Right => Down,
-            Down => Left,
-            Left => Up,
-            Up => Right,
-        }
-    }
-
-    fn walk(self, p: Point) -> Point {
-        use Direction::*;
-        match self {
-            Right => point_add(p, [1, 0]),
-            Left => point_add(p, [-1, 0]),
-            Down => point_add(p, [0, 1]),
-            Up => point_add(p, [0, -1]),
-        }
-    }
+// the score of a facing counts clockwise from east
+fn facing(d: Dir4) -> i64 {
+    (d.index() as i64 + 3) % 4
 }
 
 pub struct Player {
     position: Point,
-    direction: Direction,
+    direction: Dir4,
 }
 
 impl Player {
-    pub fn execute(&mut self, map: &HashMap<Point, Pieces>, i: &Instruction) {
+    pub fn execute(&mut self, map: &SparseGrid<Pieces>, i: &Instruction) {
         use Instruction::*;
         use Pieces::*;
-        use Direction::*;
         if let Move(n) = i {
             for i in 0..*n {
-                let new_p = self.direction.walk(self.position);
+                let new_p = self.position + self.direction.as_point(YAxis::Down);
                 match map.get(&new_p) {
                     Some(Wall) => { break; }
                     Some(Empty) => { self.position = new_p; }
                     Some(OutOfMap) | None => {
-                        let mut p = self.position;
-                        match self.direction {
-                            Up => {
-                                let max_y = map.iter().map(|(p, _)| p[1]).max().unwrap() + 1;
-                                p = [self.position[0], max_y];
-                            },
-                            Down => {
-                                let min_y = map.iter().map(|(p, _)| p[1]).min().unwrap() - 1;
-                                p = [self.position[0], min_y];
-                            },
-                            Left => {
-                                let max_x = map.iter().map(|(p, _)| p[0]).max().unwrap() + 1;
-                                p = [max_x, self.position[1]];
-                            }
-                            Right => {
-                                let min_x = map.iter().map(|(p, _)| p[0]).min().unwrap() - 1;
-                                p = [min_x, self.position[1]];
-                            }
-                        }
+                        let (min, max) = map.extents();
+                        let mut p = match self.direction {
+                            Dir4::North => Point::new(self.position.x, max.y + 1),
+                            Dir4::South => Point::new(self.position.x, min.y - 1),
+                            Dir4::West => Point::new(max.x + 1, self.position.y),
+                            Dir4::East => Point::new(min.x - 1, self.position.y),
+                        };
                         loop {
                             match map.get(&p) {
                                 Some(Wall) => { break; },
                                 Some(Empty) => { self.position = p; break; }
                                 Some(OutOfMap) | None => ()
                             };
-                            p = self.direction.walk(p)
+                            p += self.direction.as_point(YAxis::Down)
                         }
                     }
                 }
             }
         } else {
-            self.direction = self.direction.turn(i);
+            self.direction = match i {
+                L => self.direction.turn_left(),
+                _ => self.direction.turn_right(),
+            };
         }
     }
 }
 
 
-type Data = (HashMap<Point, Pieces>, Vec<Instruction>);
+type Data = (SparseGrid<Pieces>, Vec<Instruction>);
 
 
-#[aoc_generator(day22)]
 pub fn generator(input: &str) -> Data {
     let mut parts = input.split("\n\n");
     let map = parts.next().unwrap();
     let ins = parts.next().unwrap();
-    let board = map.lines().enumerate().fold(HashMap::new(), |acc, (y, line)| {
-        line.chars().enumerate().into_iter().fold(acc, |mut acc, (x, c)| {
-            match c {
-                ' ' => (),
-                '.' => { acc.insert([(x as i64) + 1, (y as i64) + 1], Pieces::Empty); },
-                '#' => { acc.insert([(x as i64) + 1, (y as i64) + 1], Pieces::Wall); },
-                _ => unreachable!()
-            }
-            acc
-        })
-    });
+    let board = SparseGrid::from(GridParser::new().origin(Point::new(1, 1)).skip(' ').parse_sparse(map, |c| match c {
+        '.' => Some(Pieces::Empty),
+        '#' => Some(Pieces::Wall),
+        _ => unreachable!()
+    }).grid);
 
     let instructions = ins.split_inclusive(['R', 'L']).fold(Vec::new(), |mut acc, i| {
         if let Ok(i) = i[0..i.len()-1].parse::<usize>() {
@@ -156,32 +95,43 @@ pub fn generator(input: &str) -> Data {
 }
 
 
-#[aoc(day22, part1)]
 pub fn part1(inputs: &Data) -> i64 {
     let (board, ins) = inputs;
-    let start = (1..).into_iter().map(|x| [x, 1]).filter_map(|p| {
-        match board.get(&p) {
-            Some(Pieces::Empty) => Some(p),
-            _ => None
-        }
-    }).next().unwrap();
+    let start = (1..).map(|x| Point::new(x, 1)).find(|p| matches!(board.get(p), Some(Pieces::Empty))).unwrap();
     let mut p = Player {
-        direction: Direction::Right,
+        direction: Dir4::East,
         position: start,
     };
     for i in ins {
         p.execute(board, i);
     }
-    p.position[1] * 1000 + p.position[0] * 4 + p.direction as i64
+    p.position.y * 1000 + p.position.x * 4 + facing(p.direction)
 }
 
 
-#[aoc(day22, part2)]
 pub fn part2(inputs: &Data) -> i64 {
     0
 }
 
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u32 = 22;
+    const TITLE: &'static str = "Monkey Map";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
 
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -212,4 +162,9 @@ mod tests {
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 0);
     }
+
+    #[test]
+    pub fn test_facing() {
+        assert_eq!(Dir4::ALL.map(facing), [3, 0, 1, 2]);
+    }
 }
@@ -1,150 +1,358 @@
 use std::collections::HashMap;
+use std::fmt;
 use aoc::*;
+use aoc::sim::{self, Simulation};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Clone)]
-pub struct Elve {
-    proposed: Option<Point>,
-    directions: [Point; 4]
+// Row-major bitboard, bit c of row r is the cell in column c. Rows are stored as u64 words
+// so every operation below is a plain loop over words the compiler can vectorize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Board {
+    words: usize,
+    height: usize,
+    bits: Vec<u64>,
 }
 
-impl Default for Elve {
-    fn default() -> Self {
-        Elve {
-            proposed: None,
-            directions: [NORTH, SOUTH, WEST, EAST],
+impl Board {
+    fn empty(words: usize, height: usize) -> Board {
+        Board { words, height, bits: vec![0; words * height] }
+    }
+
+    fn width(&self) -> usize {
+        self.words * 64
+    }
+
+    fn get(&self, c: usize, r: usize) -> bool {
+        self.bits[r * self.words + c / 64] & (1 << (c % 64)) != 0
+    }
+
+    fn set(&mut self, c: usize, r: usize) {
+        self.bits[r * self.words + c / 64] |= 1 << (c % 64);
+    }
+
+    fn row(&self, r: usize) -> &[u64] {
+        &self.bits[r * self.words..(r + 1) * self.words]
+    }
+
+    fn zip_with(&mut self, other: &Board, f: impl Fn(u64, u64) -> u64) {
+        self.bits.iter_mut().zip(other.bits.iter()).for_each(|(a, &b)| *a = f(*a, b));
+    }
+
+    fn count(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    // move every bit by d, with d in the y-up convention of the aoc direction constants
+    fn translate(&self, d: Point) -> Board {
+        let Point { x: dx, y: dy } = d;
+        let mut out = Board::empty(self.words, self.height);
+        for r in 0..self.height {
+            let src = r as i64 + dy;
+            if src < 0 || src >= self.height as i64 {
+                continue;
+            }
+            let src = self.row(src as usize);
+            let dst = &mut out.bits[r * self.words..(r + 1) * self.words];
+            shift_row(src, dx, dst);
         }
+        out
+    }
+}
+
+// dst[c] = src[c - dx]
+fn shift_row(src: &[u64], dx: i64, dst: &mut [u64]) {
+    let words = src.len() as i64;
+    let (word_shift, bit_shift) = (dx.div_euclid(64), dx.rem_euclid(64) as u32);
+    for (i, d) in dst.iter_mut().enumerate() {
+        let j = i as i64 - word_shift;
+        let word = |k: i64| if (0..words).contains(&k) { src[k as usize] } else { 0 };
+        *d = if bit_shift == 0 {
+            word(j)
+        } else {
+            (word(j) << bit_shift) | (word(j - 1) >> (64 - bit_shift))
+        };
     }
 }
 
-pub trait ElveMovement {
-    fn round(&mut self) -> usize;
-    fn propose(&mut self);
-    fn mv(&mut self) -> usize;
-    fn empty_rectiles(&self) -> i64;
-    fn print(&self);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub step: Point,
+    pub check: Vec<Point>,
 }
 
-impl ElveMovement for Data {
-    fn round(&mut self) -> usize {
-        self.propose();
-        return self.mv()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSet {
+    // an elf with none of these occupied stays where it is
+    pub idle: Vec<Point>,
+    pub rules: Vec<Rule>,
+    pub rotate: bool,
+}
+
+impl RuleSet {
+    pub fn standard() -> RuleSet {
+        RuleSet {
+            idle: Dir8::ALL.map(|d| d.as_point(YAxis::Up)).to_vec(),
+            // look at the heading and the diagonals on either side of it
+            rules: [Dir8::North, Dir8::South, Dir8::West, Dir8::East].map(|d| Rule {
+                step: d.as_point(YAxis::Up),
+                check: [d.turn_left(), d, d.turn_right()].map(|c| c.as_point(YAxis::Up)).to_vec(),
+            }).to_vec(),
+            rotate: true,
+        }
     }
 
-    fn propose(&mut self) {
-        let map = self.clone();
-        'outer: for (p, mut e) in self.iter_mut() {
-            if neighbors_incl_diagonals(*p).all(|x| map.get(&x).is_none()) {
-                e.proposed = None;
-                continue 'outer
-            }
-            for d in e.directions.iter() {
-                let to_check = match *d {
-                    NORTH => [NORTH_EAST, NORTH, NORTH_WEST],
-                    SOUTH => [SOUTH_WEST, SOUTH, SOUTH_EAST],
-                    WEST => [NORTH_WEST, WEST, SOUTH_WEST],
-                    EAST => [NORTH_EAST, EAST, SOUTH_EAST],
-                    _ => unreachable!(),
-                };
-                if to_check.iter().map(|delta| map.get(&point_add(*p, *delta))).all(|x| x.is_none()) {
-                    e.proposed = Some(point_add(*p, *d));
-                    continue 'outer
-                }
-            }
-            e.proposed = None;
+    fn reach(&self) -> usize {
+        self.idle.iter()
+            .chain(self.rules.iter().flat_map(|r| r.check.iter().chain([&r.step])))
+            .flat_map(|p| [p.x.unsigned_abs(), p.y.unsigned_abs()])
+            .max()
+            .unwrap_or(0) as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundStats {
+    pub round: usize,
+    pub moved: usize,
+    pub extents: (Point, Point),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diffusion {
+    board: Board,
+    // puzzle coordinates of column 0 / row 0
+    origin: Point,
+    rules: RuleSet,
+    first_rule: usize,
+    round: usize,
+    moved: Option<usize>,
+}
+
+impl Diffusion {
+    pub fn new(elves: &[Point], rules: RuleSet) -> Diffusion {
+        let min_x = elves.iter().map(|p| p.x).min().unwrap_or(0);
+        let max_y = elves.iter().map(|p| p.y).max().unwrap_or(0);
+        let width = elves.iter().map(|p| (p.x - min_x) as usize).max().unwrap_or(0) + 1;
+        let height = elves.iter().map(|p| (max_y - p.y) as usize).max().unwrap_or(0) + 1;
+        let mut board = Board::empty(width.div_ceil(64), height);
+        for p in elves {
+            board.set((p.x - min_x) as usize, (max_y - p.y) as usize);
+        }
+        Diffusion { board, origin: Point::new(min_x, max_y), rules, first_rule: 0, round: 0, moved: None }
+    }
+
+    fn to_point(&self, c: usize, r: usize) -> Point {
+        Point::new(self.origin.x + c as i64, self.origin.y - r as i64)
+    }
+
+    pub fn elves(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.board.height)
+            .flat_map(move |r| (0..self.board.width()).map(move |c| (c, r)))
+            .filter(|&(c, r)| self.board.get(c, r))
+            .map(|(c, r)| self.to_point(c, r))
+    }
+
+    pub fn extents(&self) -> (Point, Point) {
+        let rows = (0..self.board.height).filter(|&r| self.board.row(r).iter().any(|&w| w != 0)).collect::<Vec<_>>();
+        let (Some(&top), Some(&bottom)) = (rows.first(), rows.last()) else {
+            return (self.origin, self.origin);
+        };
+        let columns = (0..self.board.height).fold(vec![0u64; self.board.words], |mut acc, r| {
+            acc.iter_mut().zip(self.board.row(r)).for_each(|(a, w)| *a |= w);
+            acc
+        });
+        let left = columns.iter().position(|&w| w != 0).unwrap();
+        let right = columns.iter().rposition(|&w| w != 0).unwrap();
+        let left = left * 64 + columns[left].trailing_zeros() as usize;
+        let right = right * 64 + 63 - columns[right].leading_zeros() as usize;
+        (self.to_point(left, bottom), self.to_point(right, top))
+    }
+
+    pub fn empty_tiles(&self) -> i64 {
+        let (min, max) = self.extents();
+        (max.x - min.x + 1) * (max.y - min.y + 1) - self.board.count() as i64
+    }
+
+    // keep enough free border that no rule can look or step outside the board
+    fn ensure_margin(&mut self) {
+        let margin = self.rules.reach();
+        let (min, max) = self.extents();
+        let [left, top] = [(min.x - self.origin.x) as usize, (self.origin.y - max.y) as usize];
+        let [right, bottom] = [(max.x - self.origin.x) as usize, (self.origin.y - min.y) as usize];
+        let grow_x = left < margin || right + margin >= self.board.width();
+        let grow_y = top < margin || bottom + margin >= self.board.height;
+        if !grow_x && !grow_y {
+            return;
+        }
+        let pad_words = if grow_x { margin.div_ceil(64) } else { 0 };
+        let pad_rows = if grow_y { margin.max(8) } else { 0 };
+        let mut board = Board::empty(self.board.words + 2 * pad_words, self.board.height + 2 * pad_rows);
+        for r in 0..self.board.height {
+            let start = (r + pad_rows) * board.words + pad_words;
+            board.bits[start..start + self.board.words].copy_from_slice(self.board.row(r));
         }
+        self.origin = Point::new(self.origin.x - 64 * pad_words as i64, self.origin.y + pad_rows as i64);
+        self.board = board;
     }
 
-    fn mv(&mut self) -> usize {
+    pub fn round(&mut self) -> RoundStats {
+        self.ensure_margin();
+        let occupied = &self.board;
+        let mut neighbors: HashMap<Point, Board> = HashMap::new();
+        for &d in self.rules.idle.iter().chain(self.rules.rules.iter().flat_map(|r| &r.check)) {
+            neighbors.entry(d).or_insert_with(|| occupied.translate(-d));
+        }
+        let occupied_at = |d: Point| &neighbors[&d];
+
+        let mut crowded = Board::empty(occupied.words, occupied.height);
+        for &d in &self.rules.idle {
+            crowded.zip_with(occupied_at(d), |a, b| a | b);
+        }
+        let mut remaining = occupied.clone();
+        remaining.zip_with(&crowded, |a, b| a & b);
+
+        let n = self.rules.rules.len();
+        let mut proposals = vec![];
+        for k in 0..n {
+            let rule = &self.rules.rules[(self.first_rule + k) % n];
+            let mut proposing = remaining.clone();
+            for &d in &rule.check {
+                proposing.zip_with(occupied_at(d), |a, b| a & !b);
+            }
+            remaining.zip_with(&proposing, |a, b| a & !b);
+            proposals.push((rule.step, proposing.translate(rule.step)));
+        }
+
+        // a target is only valid if exactly one elf proposed it and nobody stays there
+        let mut once = Board::empty(occupied.words, occupied.height);
+        let mut twice = once.clone();
+        for (_, dest) in &proposals {
+            let mut both = once.clone();
+            both.zip_with(dest, |o, d| o & d);
+            twice.zip_with(&both, |t, b| t | b);
+            once.zip_with(dest, |o, d| o | d);
+        }
+        let mut valid = once;
+        valid.zip_with(&twice, |a, b| a & !b);
+        let mut staying = occupied.clone();
+        for (step, dest) in &proposals {
+            staying.zip_with(&dest.translate(-*step), |a, b| a & !b);
+        }
+        valid.zip_with(&staying, |a, b| a & !b);
+
+        let mut next = occupied.clone();
         let mut moved = 0;
-        let map = self.clone();
-        let targets: HashMap<Point, usize> = map
-            .iter()
-            .filter(|(_p, e)| e.proposed.is_some())
-            .fold(HashMap::new(), |mut map, (_origin, e) | {
-                *map.entry(e.proposed.unwrap()).or_insert(0) += 1;
-                map
-            });
-
-        for (from, _e) in map.iter().filter(|(_, e)| e.proposed.is_some() && *targets.get(&e.proposed.unwrap()).unwrap() == 1) {
-            moved += 1;
-            let mut e = self.remove(from).unwrap();
-            let to = e.proposed.unwrap();
-            e.proposed = None;
-            self.insert(to, e);
+        for (step, dest) in &proposals {
+            let mut arrived = dest.clone();
+            arrived.zip_with(&valid, |a, b| a & b);
+            moved += arrived.count();
+            next.zip_with(&arrived.translate(-*step), |a, b| a & !b);
+            next.zip_with(&arrived, |a, b| a | b);
+        }
+
+        self.board = next;
+        if self.rules.rotate && n > 0 {
+            self.first_rule = (self.first_rule + 1) % n;
         }
+        self.round += 1;
+        self.moved = Some(moved);
+        RoundStats { round: self.round, moved, extents: self.extents() }
+    }
 
-        for (_, mut e) in self.iter_mut() {
-            if e.proposed.is_some() {
-                e.proposed = None;
+    // run until no elf moves or max_rounds is reached
+    pub fn run(&mut self, max_rounds: Option<usize>) -> Vec<RoundStats> {
+        let mut stats = vec![];
+        while max_rounds.map(|m| stats.len() < m).unwrap_or(true) {
+            let s = self.round();
+            stats.push(s);
+            if s.moved == 0 {
+                break;
             }
-            e.directions.rotate_left(1)
         }
-        moved
-    }
-
-    fn empty_rectiles(&self) -> i64 {
-        let minx = self.iter().map(|(p, _)| p[0]).min().unwrap();
-        let maxx = self.iter().map(|(p, _)| p[0]).max().unwrap();
-        let miny = self.iter().map(|(p, _)| p[1]).min().unwrap();
-        let maxy = self.iter().map(|(p, _)| p[1]).max().unwrap();
-        let elves = self.iter().count() as i64;
-        ((maxx - minx + 1) * (maxy - miny + 1)) - elves
-    }
-
-    fn print(&self) {
-        for y in (-11..=0).rev() {
-            for x in 0..14 {
-                if self.get(&[x, y]).is_some() {
-                    print!("#")
-                } else {
-                    print!(".");
-                }
+        stats
+    }
+}
+
+// a round per step, done once nobody moved
+impl Simulation for Diffusion {
+    type State = Self;
+    type Snapshot = Self;
+
+    fn step(&mut self) {
+        self.round();
+    }
+
+    fn state(&self) -> &Self {
+        self
+    }
+
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: &Self) {
+        *self = snapshot.clone();
+    }
+
+    fn is_done(&self) -> bool {
+        self.moved == Some(0)
+    }
+}
+
+impl fmt::Display for Diffusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = self.extents();
+        for y in (min.y..=max.y).rev() {
+            for x in min.x..=max.x {
+                let [c, r] = [(x - self.origin.x) as usize, (self.origin.y - y) as usize];
+                write!(f, "{}", if self.board.get(c, r) { '#' } else { '.' })?;
             }
-            println!();
+            writeln!(f)?;
         }
-        println!();
+        Ok(())
     }
 }
 
-type Data = HashMap<Point, Elve>;
+type Data = Vec<Point>;
 
 
-#[aoc_generator(day23)]
 pub fn generator(input: &str) -> Data {
-    input.lines().enumerate().fold(HashMap::new(), |map, (negy, line)| {
-        line.chars().enumerate().fold(map, |mut map, (x, c)| {
-            if c == '#' {
-                map.insert([x as i64, -(negy as i64)], Elve::default());
-            }
-            map
-        })
-    })
+    GridParser::new().y_up().parse_sparse(input, |c| (c == '#').then_some(())).grid.into_keys().collect()
 }
 
 
 
-#[aoc(day23, part1)]
 pub fn part1(inputs: &Data) -> i64 {
-    let mut map = inputs.clone();
-    for _i in 0..10 {
-        map.round();
-    }
-    map.empty_rectiles()
+    let mut elves = Diffusion::new(inputs, RuleSet::standard());
+    sim::run(&mut elves, 10);
+    elves.empty_tiles()
 }
 
 
-#[aoc(day23, part2)]
-pub fn part2(inputs: &Data) -> i32 {
-    let mut map = inputs.clone();
-    for i in 1.. {
-        if map.round() == 0 {
-            return i
-        }
-    }
-    0
+pub fn part2(inputs: &Data) -> usize {
+    let mut elves = Diffusion::new(inputs, RuleSet::standard());
+    sim::run_to_end(&mut elves)
 }
 
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u32 = 23;
+    const TITLE: &'static str = "Unstable Diffusion";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
+
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
 
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -173,4 +381,49 @@ mod tests {
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 20);
     }
+
+    #[test]
+    pub fn test_small() {
+        let mut elves = Diffusion::new(&generator(".....\n..##.\n..#..\n.....\n..##.\n....."), RuleSet::standard());
+        let stats = elves.run(None);
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats[3].moved, 0);
+        assert_eq!(elves.to_string(), "..#..\n....#\n#....\n....#\n.....\n..#..\n");
+    }
+
+    #[test]
+    pub fn test_rules() {
+        // only the orthogonal neighbours count, east is tried before north and the order never
+        // rotates
+        let heading = |d: Dir8| d.as_point(YAxis::Up);
+        let rules = RuleSet {
+            idle: [Dir8::North, Dir8::East, Dir8::South, Dir8::West].map(heading).to_vec(),
+            rules: [Dir8::East, Dir8::North].map(|d| Rule { step: heading(d), check: vec![heading(d)] }).to_vec(),
+            rotate: false,
+        };
+        let mut elves = Diffusion::new(&generator("###"), rules.clone());
+        let moved = elves.run(None).iter().map(|s| s.moved).collect::<Vec<_>>();
+        assert_eq!(moved, vec![3, 2, 0]);
+        assert_eq!(elves.to_string(), "#...\n..#.\n...#\n");
+        // diagonal neighbours don't keep an elf busy
+        assert_eq!(Diffusion::new(&generator(".#.\n..#"), rules).round().moved, 0);
+        assert_eq!(Diffusion::new(&generator(".#.\n..#"), RuleSet::standard()).round().moved, 2);
+    }
+
+    #[test]
+    pub fn test_fixed_point() {
+        let mut elves = Diffusion::new(&generator(SAMPLE), RuleSet::standard());
+        let positions = |d: &Diffusion| {
+            let mut p = d.elves().collect::<Vec<_>>();
+            p.sort();
+            p
+        };
+        let history = sim::record(&mut elves, 5);
+        assert_eq!(sim::run_until_fixed_point(&mut elves, Some(10), positions), None);
+        // round 20 is the first where nobody moves
+        assert_eq!(sim::run_until_fixed_point(&mut elves, None, positions), Some(5));
+        assert!(elves.is_done());
+        elves.restore(&history[5]);
+        assert_eq!(sim::run_to_end(&mut elves), 15);
+    }
 }
@@ -1,4 +1,7 @@
-use aoc::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use aoc::sim::{self, Simulation};
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub enum Elem {
@@ -38,12 +41,142 @@ impl Elem {
             _ => *self,
         }
     }
+
+    fn position(&self) -> (usize, usize) {
+        use Elem::*;
+        match *self {
+            Up(y, x) | Down(y, x) | Left(y, x) | Right(y, x) | Wall(y, x) | Open(y, x) => (y, x),
+        }
+    }
+
+    fn symbol(&self) -> char {
+        use Elem::*;
+        match self {
+            Up(..) => '^',
+            Down(..) => 'v',
+            Left(..) => '<',
+            Right(..) => '>',
+            Wall(..) => '#',
+            Open(..) => '.',
+        }
+    }
+}
+
+// Blizzards move one minute per step and the expedition spreads to every cell it can be in,
+// positions are (row, column) like in Elem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Valley {
+    blizzards: Vec<Elem>,
+    walls: HashSet<(usize, usize)>,
+    xmax: usize,
+    ymax: usize,
+    goal: (usize, usize),
+    expedition: HashSet<(usize, usize)>,
+    minute: usize,
+    // the blizzards repeat after period minutes, an expedition that didn't grow over a whole
+    // period never reaches the goal
+    period: usize,
+    last_period: HashSet<(usize, usize)>,
+    stuck: bool,
+}
+
+impl Valley {
+    pub fn new(map: &[Elem]) -> Valley {
+        let xmax = map.iter().map(|e| e.position().1).max().unwrap_or(0);
+        let ymax = map.iter().map(|e| e.position().0).max().unwrap_or(0);
+        let open = |row| map.iter().find(|e| matches!(e, Elem::Open(..)) && e.position().0 == row).map(|e| e.position());
+        Valley {
+            blizzards: map.iter().filter(|e| !matches!(e, Elem::Wall(..) | Elem::Open(..))).copied().collect(),
+            walls: map.iter().filter(|e| matches!(e, Elem::Wall(..))).map(|e| e.position()).collect(),
+            xmax,
+            ymax,
+            goal: open(ymax).unwrap_or((ymax, xmax)),
+            expedition: open(0).into_iter().collect(),
+            minute: 0,
+            period: lcm(xmax.saturating_sub(1).max(1), ymax.saturating_sub(1).max(1)),
+            last_period: HashSet::new(),
+            stuck: false,
+        }
+    }
+
+    pub fn minute(&self) -> usize {
+        self.minute
+    }
+
+    pub fn reached_goal(&self) -> bool {
+        self.expedition.contains(&self.goal)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
+
+impl Simulation for Valley {
+    type State = Self;
+    type Snapshot = Self;
+
+    fn step(&mut self) {
+        self.blizzards.iter_mut().for_each(|b| *b = b.step(self.xmax, self.ymax));
+        let blocked = self.blizzards.iter().map(|b| b.position()).collect::<HashSet<_>>();
+        self.expedition = self.expedition.iter()
+            .flat_map(|&(y, x)| [(y, x), (y.wrapping_sub(1), x), (y + 1, x), (y, x.wrapping_sub(1)), (y, x + 1)])
+            .filter(|&(y, x)| y <= self.ymax && x <= self.xmax && !self.walls.contains(&(y, x)) && !blocked.contains(&(y, x)))
+            .collect();
+        self.minute += 1;
+        if self.minute.is_multiple_of(self.period) {
+            self.stuck = self.expedition == self.last_period;
+            self.last_period = self.expedition.clone();
+        }
+    }
+
+    fn state(&self) -> &Self {
+        self
+    }
+
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    fn restore(&mut self, snapshot: &Self) {
+        *self = snapshot.clone();
+    }
+
+    fn is_done(&self) -> bool {
+        self.reached_goal() || self.stuck
+    }
+}
+
+impl fmt::Display for Valley {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut blizzards = HashMap::new();
+        for b in &self.blizzards {
+            blizzards.entry(b.position()).or_insert(vec![]).push(b.symbol());
+        }
+        for y in 0..=self.ymax {
+            for x in 0..=self.xmax {
+                let c = match blizzards.get(&(y, x)) {
+                    _ if self.walls.contains(&(y, x)) => '#',
+                    Some(b) if b.len() == 1 => b[0],
+                    Some(b) => char::from_digit(b.len().min(9) as u32, 10).unwrap(),
+                    None if self.expedition.contains(&(y, x)) => 'E',
+                    None => '.',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
 }
 
 type Data = Vec<Elem>;
 
 
-#[aoc_generator(day24)]
 pub fn generator(input: &str) -> Data {
     input.lines().enumerate().fold(Vec::new(), |map, (y, line)| {
         line.chars().enumerate().fold(map, |mut map, (x, c)| {
@@ -57,18 +190,40 @@ pub fn generator(input: &str) -> Data {
 
 
 
-#[aoc(day24, part1)]
-pub fn part1(inputs: &Data) -> i64 {
-    0
+pub fn part1(inputs: &Data) -> Result<i64, String> {
+    let mut valley = Valley::new(inputs);
+    sim::run_to_end(&mut valley);
+    if valley.reached_goal() {
+        Ok(valley.minute() as i64)
+    } else {
+        Err("the blizzards never let the expedition through".to_string())
+    }
 }
 
 
-#[aoc(day24, part2)]
 pub fn part2(inputs: &Data) -> i32 {
     0
 }
 
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u32 = 24;
+    const TITLE: &'static str = "Blizzard Basin";
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, String> {
+        Ok(generator(input))
+    }
 
+    fn part1(input: &Data) -> Answer {
+        part1(input).into_answer()
+    }
+
+    fn part2(input: &Data) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -84,11 +239,35 @@ mod tests {
 
     #[test]
     pub fn test1() {
-        assert_eq!(part1(&generator(&SAMPLE)), 18);
+        assert_eq!(part1(&generator(&SAMPLE)), Ok(18));
     }
 
     #[test]
     pub fn test2() {
         assert_eq!(part2(&generator(&SAMPLE)), 0);
     }
+
+    #[test]
+    pub fn test_valley() {
+        let mut valley = Valley::new(&generator(SAMPLE));
+        let start = valley.snapshot();
+        sim::run(&mut valley, 1);
+        assert_eq!(valley.to_string(), "#E######\n#E>3.<.#\n#<..<<.#\n#>2.22.#\n#>v..^<#\n######.#\n");
+        sim::run(&mut valley, 11);
+        assert_eq!(valley.minute(), 12);
+        valley.restore(&start);
+        assert_eq!(valley, Valley::new(&generator(SAMPLE)));
+    }
+
+    #[test]
+    pub fn test_expedition() {
+        let mut valley = Valley::new(&generator(SAMPLE));
+        let history = sim::record(&mut valley, 100);
+        assert_eq!(history.len(), 19);
+        assert!(valley.reached_goal());
+        valley.restore(&history[1]);
+        assert_eq!(valley.to_string(), "#E######\n#E>3.<.#\n#<..<<.#\n#>2.22.#\n#>v..^<#\n######.#\n");
+        let walled = generator("#.###\n#.>.#\n#####\n#####\n###.#");
+        assert_eq!(part1(&walled), Err("the blizzards never let the expedition through".to_string()));
+    }
 }
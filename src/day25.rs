@@ -1,48 +1,248 @@
-use std::collections::{HashMap, VecDeque};
-use aoc::*;
+use std::fmt;
+use std::iter::Sum;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
 
+use thiserror::Error;
+use crate::solution::{Answer, IntoAnswer, Solution};
 
 
-pub fn from_snafu(input: &str) -> i64 {
-    input.chars().fold(0, |acc, c| {
-        (acc * 5) + match c {
-            '2' => 2,
-            '1' => 1,
-            '0' => 0,
-            '-' => -1,
-            '=' => -2,
-            _ => unreachable!()
+// Digit alphabet of a balanced base, SYMBOLS lists the digits from -(BASE / 2) to BASE / 2
+pub trait BalancedBase {
+    const BASE: i64;
+    const SYMBOLS: &'static str;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Base5;
+
+impl BalancedBase for Base5 {
+    const BASE: i64 = 5;
+    const SYMBOLS: &'static str = "=-012";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Base3;
+
+impl BalancedBase for Base3 {
+    const BASE: i64 = 3;
+    const SYMBOLS: &'static str = "-0+";
+}
+
+// Arbitrary length balanced number, digits are stored least significant first without
+// leading zeros, so zero has no digits at all.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Balanced<B: BalancedBase> {
+    digits: Vec<i8>,
+    base: PhantomData<B>,
+}
+
+pub type Snafu = Balanced<Base5>;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum BalancedError {
+    #[error("empty number")]
+    Empty,
+    #[error("invalid digit: {0:?}")]
+    InvalidDigit(char),
+    #[error("value does not fit into an i64")]
+    Overflow,
+}
+
+impl<B: BalancedBase> Balanced<B> {
+    pub fn zero() -> Self {
+        Balanced { digits: vec![], base: PhantomData }
+    }
+
+    // fails the build for any base used with an even BASE, digits that don't fit the i8 they
+    // are stored in or a SYMBOLS of the wrong length
+    const VALID: () = assert!(
+        B::BASE >= 3 && B::BASE % 2 == 1 && B::BASE / 2 <= i8::MAX as i64 && B::SYMBOLS.len() as i64 == B::BASE,
+        "a balanced base needs an odd BASE of at most 255 and one ascii symbol per digit"
+    );
+
+    fn half() -> i64 {
+        let () = Self::VALID;
+        B::BASE / 2
+    }
+
+    // bring arbitrary digit values back into -half..=half by carrying
+    fn normalize(raw: Vec<i64>) -> Self {
+        let mut digits = Vec::with_capacity(raw.len() + 1);
+        let mut carry = 0;
+        let mut raw = raw.into_iter();
+        loop {
+            let v = match raw.next() {
+                Some(v) => v + carry,
+                None if carry != 0 => carry,
+                None => break,
+            };
+            let d = (v + Self::half()).rem_euclid(B::BASE) - Self::half();
+            carry = (v - d) / B::BASE;
+            digits.push(d as i8);
+        }
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Balanced { digits, base: PhantomData }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.digits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_zero()
+    }
+}
+
+impl<B: BalancedBase> FromStr for Balanced<B> {
+    type Err = BalancedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(BalancedError::Empty);
+        }
+        let raw = s.chars().rev().map(|c| {
+            B::SYMBOLS.chars().position(|sym| sym == c)
+                .map(|p| p as i64 - Self::half())
+                .ok_or(BalancedError::InvalidDigit(c))
+        }).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::normalize(raw))
+    }
+}
+
+impl<B: BalancedBase> fmt::Display for Balanced<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = |d: i64| B::SYMBOLS.chars().nth((d + Self::half()) as usize).unwrap();
+        if self.is_zero() {
+            return write!(f, "{}", symbol(0));
+        }
+        self.digits.iter().rev().try_for_each(|&d| write!(f, "{}", symbol(d as i64)))
+    }
+}
+
+impl<B: BalancedBase> From<i64> for Balanced<B> {
+    fn from(mut n: i64) -> Self {
+        let mut raw = vec![];
+        while n != 0 {
+            raw.push(n % B::BASE);
+            n /= B::BASE;
+        }
+        Self::normalize(raw)
+    }
+}
+
+impl<B: BalancedBase> TryFrom<&Balanced<B>> for i64 {
+    type Error = BalancedError;
+
+    fn try_from(n: &Balanced<B>) -> Result<Self, Self::Error> {
+        n.digits.iter().rev().try_fold(0i64, |acc, &d| {
+            acc.checked_mul(B::BASE).and_then(|acc| acc.checked_add(d as i64))
+        }).ok_or(BalancedError::Overflow)
+    }
+}
+
+impl<B: BalancedBase> Add for &Balanced<B> {
+    type Output = Balanced<B>;
+
+    fn add(self, other: Self) -> Balanced<B> {
+        let len = self.len().max(other.len());
+        let digit = |n: &Balanced<B>, i: usize| n.digits.get(i).copied().unwrap_or(0) as i64;
+        Balanced::normalize((0..len).map(|i| digit(self, i) + digit(other, i)).collect())
+    }
+}
+
+impl<B: BalancedBase> Add for Balanced<B> {
+    type Output = Balanced<B>;
+
+    fn add(self, other: Self) -> Balanced<B> {
+        &self + &other
+    }
+}
+
+impl<B: BalancedBase> Neg for Balanced<B> {
+    type Output = Balanced<B>;
+
+    fn neg(self) -> Balanced<B> {
+        Balanced { digits: self.digits.iter().map(|d| -d).collect(), base: PhantomData }
+    }
+}
+
+impl<B: BalancedBase> Sub for Balanced<B> {
+    type Output = Balanced<B>;
+
+    fn sub(self, other: Self) -> Balanced<B> {
+        self + -other
+    }
+}
+
+impl<B: BalancedBase> Mul for &Balanced<B> {
+    type Output = Balanced<B>;
+
+    fn mul(self, other: Self) -> Balanced<B> {
+        if self.is_zero() || other.is_zero() {
+            return Balanced::zero();
+        }
+        let mut raw = vec![0i64; self.len() + other.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            for (j, &b) in other.digits.iter().enumerate() {
+                raw[i + j] += a as i64 * b as i64;
+            }
         }
-    })
+        Balanced::normalize(raw)
+    }
 }
 
-pub fn to_snafu(input: i64) -> String {
-    if input == 0 {
-        return String::new()
+impl<B: BalancedBase> Mul for Balanced<B> {
+    type Output = Balanced<B>;
+
+    fn mul(self, other: Self) -> Balanced<B> {
+        &self * &other
     }
+}
 
-    let rem = input % 5;
-    let digit = ['0', '1', '2', '=', '-'][rem as usize];
-    let new_dec = (input + 2) / 5;
-    let mut snafu = to_snafu(new_dec);
-    snafu.push(digit);
-    snafu
+impl<B: BalancedBase> Sum for Balanced<B> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Balanced::zero(), |acc, n| acc + n)
+    }
 }
 
 
 
-#[aoc(day25, part1)]
 pub fn part1(inputs: &str) -> String {
-    to_snafu(inputs.lines().map(|l| from_snafu(l)).sum())
+    inputs.lines().map(|l| l.parse::<Snafu>().unwrap()).sum::<Snafu>().to_string()
 }
 
 
-#[aoc(day25, part2)]
-pub fn part2(inputs: &str) -> i64 {
+pub fn part2(_inputs: &str) -> i64 {
     0
 }
 
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u32 = 25;
+    const TITLE: &'static str = "Full of Hot Air";
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, String> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &String) -> Answer {
+        part1(input).into_answer()
+    }
 
+    fn part2(input: &String) -> Answer {
+        part2(input).into_answer()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -62,43 +262,39 @@ mod tests {
 1=
 122";
 
+    const PAIRS: [(&str, i64); 16] = [
+        ("0", 0), ("1", 1), ("2", 2), ("1=", 3), ("1-", 4), ("10", 5), ("11", 6), ("12", 7),
+        ("2=", 8), ("2-", 9), ("20", 10), ("1=0", 15), ("1-0", 20), ("1=11-2", 2022),
+        ("1-0---0", 12345), ("1121-1110-1=0", 314159265),
+    ];
+
     #[test]
     pub fn test_from_snafu() {
-        assert_eq!(from_snafu("1"), 1);
-        assert_eq!(from_snafu("2"), 2);
-        assert_eq!(from_snafu("1="), 3);
-        assert_eq!(from_snafu("1-"), 4);
-        assert_eq!(from_snafu("10"), 5);
-        assert_eq!(from_snafu("11"), 6);
-        assert_eq!(from_snafu("12"), 7);
-        assert_eq!(from_snafu("2="), 8);
-        assert_eq!(from_snafu("2-"), 9);
-        assert_eq!(from_snafu("20"), 10);
-        assert_eq!(from_snafu("1=0"), 15);
-        assert_eq!(from_snafu("1-0"), 20);
-        assert_eq!(from_snafu("1=11-2"), 2022);
-        assert_eq!(from_snafu("1-0---0"), 12345);
-        assert_eq!(from_snafu("1121-1110-1=0"), 314159265);
-
+        for (s, n) in PAIRS {
+            assert_eq!(i64::try_from(&s.parse::<Snafu>().unwrap()), Ok(n));
+        }
+        assert_eq!("12a".parse::<Snafu>(), Err(BalancedError::InvalidDigit('a')));
+        assert_eq!("".parse::<Snafu>(), Err(BalancedError::Empty));
     }
 
     #[test]
     pub fn test_to_snafu() {
-        assert_eq!(to_snafu(1), "1");
-        assert_eq!(to_snafu(2), "2");
-        assert_eq!(to_snafu(3), "1=");
-        assert_eq!(to_snafu(4), "1-");
-        assert_eq!(to_snafu(5), "10");
-        assert_eq!(to_snafu(6), "11");
-        assert_eq!(to_snafu(7), "12");
-        assert_eq!(to_snafu(8), "2=");
-        assert_eq!(to_snafu(9), "2-");
-        assert_eq!(to_snafu(10), "20");
-        assert_eq!(to_snafu(15), "1=0");
-        assert_eq!(to_snafu(20), "1-0");
-        assert_eq!(to_snafu(2022), "1=11-2");
-        assert_eq!(to_snafu(12345), "1-0---0");
-        assert_eq!(to_snafu(314159265), "1121-1110-1=0");
+        for (s, n) in PAIRS {
+            assert_eq!(Snafu::from(n).to_string(), s);
+        }
+    }
+
+    #[test]
+    pub fn test_arithmetic() {
+        let a = Snafu::from(2022);
+        let b = Snafu::from(-345);
+        assert_eq!(&a + &b, Snafu::from(1677));
+        assert_eq!(a.clone() - b.clone(), Snafu::from(2367));
+        assert_eq!(&a * &b, Snafu::from(-697590));
+        assert_eq!(-a.clone() + a, Snafu::zero());
+        assert_eq!(Balanced::<Base3>::from(8).to_string(), "+0-");
+        let big = "2".repeat(40).parse::<Snafu>().unwrap();
+        assert_eq!(i64::try_from(&(&big * &big)), Err(BalancedError::Overflow));
     }
 
     #[test]
@@ -0,0 +1,136 @@
+// Runs every implementation of one day and part on the same input and checks that they agree.
+use std::fmt;
+
+use crate::solution::{Answer, Entry};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub day: u32,
+    pub part: u8,
+    pub answers: Vec<(String, Answer)>,
+}
+
+impl fmt::Display for Disagreement {
+    // the first implementation is the reference, answers that differ from it are marked with !
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "day{:02} part{}: implementations disagree", self.day, self.part)?;
+        if self.answers.is_empty() {
+            return writeln!(f, "  no implementations");
+        }
+        let reference = &self.answers[0].1;
+        for (name, answer) in &self.answers {
+            let marker = if answer == reference { ' ' } else { '!' };
+            let answer = match answer {
+                Ok(a) => a.clone(),
+                Err(e) => format!("error: {}", e),
+            };
+            writeln!(f, "{} {:<24} {}", marker, name, answer)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Disagreement {}
+
+// a panic counts as an answer of its own, so a variant that crashes disagrees with one that doesn't
+pub fn run(entry: &Entry, input: &str) -> Answer {
+    entry.run(input).answer
+}
+
+// the main implementation of a part followed by its variants
+pub fn implementations(entries: &[Entry], day: u32, part: u8) -> Vec<&Entry> {
+    let mut found = entries.iter().filter(|e| e.matches(Some(day), Some(part))).collect::<Vec<_>>();
+    found.sort_by_key(|e| e.variant.is_some());
+    found
+}
+
+// every day and part that has more than one implementation
+pub fn with_variants(entries: &[Entry]) -> Vec<(u32, u8)> {
+    let mut parts = entries.iter().filter(|e| e.variant.is_some()).map(|e| (e.day, e.part)).collect::<Vec<_>>();
+    parts.sort();
+    parts.dedup();
+    parts
+}
+
+pub fn compare(entries: &[Entry], day: u32, part: u8, input: &str) -> Result<Answer, Disagreement> {
+    let answers = implementations(entries, day, part).into_iter()
+        .map(|e| (e.name(), run(e, input)))
+        .collect::<Vec<_>>();
+    match answers.first() {
+        None => Err(Disagreement { day, part, answers }),
+        Some((_, first)) if answers.iter().all(|(_, a)| a == first) => Ok(first.clone()),
+        Some(_) => Err(Disagreement { day, part, answers }),
+    }
+}
+
+// for tests, panics with the table of answers if the implementations disagree
+pub fn assert_agree(entries: &[Entry], day: u32, part: u8, input: &str) -> Answer {
+    compare(entries, day, part, input).unwrap_or_else(|d| panic!("{}", d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use crate::inputgen;
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use regex::Regex;
+
+    fn random_input(day: u32, rng: &mut StdRng) -> Option<String> {
+        let (a, b) = (rng.gen_range(0..2000), rng.gen_range(0..2000));
+        match day {
+            6 => Some(inputgen::day06(rng, a, 2 + (b % 24) as u8)),
+            7 => Some(inputgen::day07(rng, a % 40)),
+            12 => Some(inputgen::day12(rng, 14 + a % 26, 14 + b % 16)),
+            15 => {
+                // a small layout moved onto the row part1 looks at, the full size is too slow here
+                let input = inputgen::day15(rng, 100_000, 1 + a % 4);
+                let y = Regex::new(r"y=(-?\d+)").unwrap();
+                Some(y.replace_all(&input, |c: &regex::Captures| format!("y={}", c[1].parse::<i64>().unwrap() + 1_950_000)).into_owned())
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    pub fn test_discovery() {
+        assert_eq!(with_variants(&registry()), vec![(6, 1), (6, 2), (7, 1), (7, 2), (12, 1), (12, 2), (15, 1)]);
+        let entries = registry();
+        let names = implementations(&entries, 6, 1).iter().map(|e| e.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["day06 part1", "day06 part1 (noset)"]);
+    }
+
+    #[test]
+    pub fn test_disagreement() {
+        let entries = registry();
+        // a part without variants always agrees with itself, a missing one never does
+        assert_eq!(compare(&entries, 2, 1, "A Y"), Ok(Ok("8".to_string())));
+        assert!(compare(&entries, 2, 3, "A Y").is_err());
+        let d = Disagreement {
+            day: 6,
+            part: 1,
+            answers: vec![
+                ("day06 part1".to_string(), Ok("5".to_string())),
+                ("day06 part1 (noset)".to_string(), Err("panicked: boom".to_string())),
+            ],
+        };
+        assert_eq!(d.to_string(), "day06 part1: implementations disagree\n  day06 part1              5\n! day06 part1 (noset)      error: panicked: boom\n");
+    }
+
+    #[test]
+    pub fn test_random() {
+        let entries = registry();
+        for (day, part) in with_variants(&entries) {
+            // day15 part1 scans ten million cells in its naive variant
+            let runs = if day == 15 { 2 } else { 20 };
+            for seed in 0..runs {
+                let mut rng = inputgen::rng(seed);
+                let input = random_input(day, &mut rng).unwrap_or_else(|| panic!("no input generator for day {}", day));
+                if let Err(d) = compare(&entries, day, part, &input) {
+                    panic!("seed {}\n{}", seed, d);
+                }
+            }
+        }
+    }
+}
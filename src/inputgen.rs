@@ -0,0 +1,597 @@
+// Seeded generators for valid puzzle inputs, in the same text format as the real ones.
+// Every generator takes its own size knobs, generate maps a single size onto them so all days
+// can be scaled together.
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::day24;
+use crate::day25::Snafu;
+
+pub fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+// Input for a day where size roughly counts lines, for grids it is the side length. None for
+// days without a generator and when day24 gives up.
+pub fn generate(day: u32, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut rng(seed);
+    let size = size.max(1);
+    let input = match day {
+        1 => day01(rng, size.max(3), 8),
+        2 => day02(rng, size),
+        3 => day03(rng, size.div_ceil(3)),
+        4 => day04(rng, size),
+        5 => day05(rng, size),
+        6 => day06(rng, size, 14),
+        7 => day07(rng, size),
+        8 => day08(rng, size, size),
+        9 => day09(rng, size, 20),
+        10 => day10(rng),
+        11 => day11(rng, size.clamp(2, 8), 4),
+        12 => day12(rng, size.max(14), size.max(14)),
+        13 => day13(rng, size, 4),
+        14 => day14(rng, size, 60, 160),
+        15 => day15(rng, 4_000_000, size),
+        16 => day16(rng, size.max(2) * 3, size.clamp(1, 15)),
+        17 => day17(rng, size),
+        19 => day19(rng, size),
+        20 => day20(rng, size),
+        21 => day21(rng, size),
+        22 => day22(rng, size.max(2), size * 4),
+        23 => day23(rng, size, 50),
+        24 => day24(rng, size.max(3) * 3, size.max(3), 60).ok()?,
+        25 => day25(rng, size),
+        _ => return None,
+    };
+    Some(input)
+}
+
+fn join(lines: impl IntoIterator<Item = String>) -> String {
+    lines.into_iter().collect::<Vec<_>>().join("\n")
+}
+
+pub fn day01(rng: &mut StdRng, elves: usize, max_items: usize) -> String {
+    (0..elves).map(|_| {
+        join((0..rng.gen_range(1..=max_items)).map(|_| rng.gen_range(1000..60_000).to_string()))
+    }).collect::<Vec<_>>().join("\n\n")
+}
+
+pub fn day02(rng: &mut StdRng, rounds: usize) -> String {
+    join((0..rounds).map(|_| format!("{} {}", ['A', 'B', 'C'][rng.gen_range(0..3)], ['X', 'Y', 'Z'][rng.gen_range(0..3)])))
+}
+
+// every rucksack shares exactly one item between its halves and every group exactly one badge
+pub fn day03(rng: &mut StdRng, groups: usize) -> String {
+    let items = ('a'..='z').chain('A'..='Z').collect::<Vec<_>>();
+    join((0..groups).flat_map(|_| {
+        let mut items = items.clone();
+        items.shuffle(rng);
+        let badge = items.pop().unwrap();
+        // disjoint pools so only the badge is in all three rucksacks
+        items.chunks(items.len() / 3).take(3).map(|pool| {
+            let (left_pool, right_pool) = pool.split_at(pool.len() / 2);
+            let common = if rng.gen_bool(0.3) { badge } else { *pool.choose(rng).unwrap() };
+            let (left_pool, right_pool) = (
+                left_pool.iter().filter(|&&c| c != common).copied().collect::<Vec<_>>(),
+                right_pool.iter().filter(|&&c| c != common).copied().collect::<Vec<_>>(),
+            );
+            let half = rng.gen_range(4..16);
+            let mut left = vec![common, badge];
+            let mut right = vec![common];
+            left.extend((left.len()..half).map(|_| *left_pool.choose(rng).unwrap()));
+            right.extend((right.len()..half).map(|_| *right_pool.choose(rng).unwrap()));
+            left.shuffle(rng);
+            right.shuffle(rng);
+            left.into_iter().chain(right).collect::<String>()
+        }).collect::<Vec<_>>()
+    }))
+}
+
+pub fn day04(rng: &mut StdRng, pairs: usize) -> String {
+    let range = |rng: &mut StdRng| {
+        let a = rng.gen_range(1..99);
+        (a, rng.gen_range(a..100))
+    };
+    join((0..pairs).map(|_| {
+        let ((a, b), (c, d)) = (range(rng), range(rng));
+        format!("{}-{},{}-{}", a, b, c, d)
+    }))
+}
+
+// nine stacks, moves never empty a stack so both parts always find a crate on top
+pub fn day05(rng: &mut StdRng, moves: usize) -> String {
+    let mut heights = (0..9).map(|_| rng.gen_range(1..=8)).collect::<Vec<usize>>();
+    let mut lines = (0..8).rev().map(|level| {
+        heights.iter().map(|&h| {
+            if h > level { format!("[{}]", rng.gen_range('A'..='Z')) } else { "   ".to_string() }
+        }).collect::<Vec<_>>().join(" ")
+    }).collect::<Vec<_>>();
+    lines.push(" 1   2   3   4   5   6   7   8   9 ".to_string());
+    lines.push(String::new());
+    for _ in 0..moves {
+        let from = (0..9).filter(|&i| heights[i] > 1).collect::<Vec<_>>();
+        let Some(&from) = from.choose(rng) else { break };
+        let to = (from + rng.gen_range(1..9)) % 9;
+        let n = rng.gen_range(1..heights[from]);
+        heights[from] -= n;
+        heights[to] += n;
+        lines.push(format!("move {} from {} to {}", n, from + 1, to + 1));
+    }
+    join(lines)
+}
+
+// random signal over the first alphabet letters with a 14 letter marker at the end
+pub fn day06(rng: &mut StdRng, len: usize, alphabet: u8) -> String {
+    let alphabet = alphabet.clamp(1, 26);
+    let mut s = (0..len).map(|_| (b'a' + rng.gen_range(0..alphabet)) as char).collect::<String>();
+    let mut tail = ('a'..='z').collect::<Vec<_>>();
+    tail.shuffle(rng);
+    s.extend(&tail[..14]);
+    s
+}
+
+// depth first walk that lists every directory once, the total size is between 40M and 70M
+pub fn day07(rng: &mut StdRng, dirs: usize) -> String {
+    fn walk(rng: &mut StdRng, out: &mut Vec<String>, dirs: &mut usize, depth: usize) {
+        out.push("$ ls".to_string());
+        let subdirs = if depth < 8 { rng.gen_range(0..=(*dirs).min(4)) } else { 0 };
+        *dirs -= subdirs;
+        let mut entries = (0..subdirs).map(|i| format!("dir d{}", i)).collect::<Vec<_>>();
+        for i in 0..rng.gen_range(1..4) {
+            entries.push(format!("{} f{}.txt", rng.gen_range(1..200_000), i));
+        }
+        entries.shuffle(rng);
+        out.extend(entries);
+        for i in 0..subdirs {
+            out.push(format!("$ cd d{}", i));
+            walk(rng, out, dirs, depth + 1);
+            out.push("$ cd ..".to_string());
+        }
+    }
+    let mut out = vec!["$ cd /".to_string()];
+    let mut dirs = dirs;
+    walk(rng, &mut out, &mut dirs, 0);
+    out.insert(2, format!("{} big.bin", rng.gen_range(40_000_000..60_000_000)));
+    // the transcript is allowed to end somewhere below the root
+    while out.last().map(String::as_str) == Some("$ cd ..") && rng.gen_bool(0.5) {
+        out.pop();
+    }
+    join(out)
+}
+
+pub fn day08(rng: &mut StdRng, width: usize, height: usize) -> String {
+    join((0..height).map(|_| (0..width).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect()))
+}
+
+pub fn day09(rng: &mut StdRng, moves: usize, max_step: usize) -> String {
+    join((0..moves).map(|_| format!("{} {}", ['U', 'D', 'L', 'R'][rng.gen_range(0..4)], rng.gen_range(1..=max_step))))
+}
+
+// exactly the 240 cycles the screen has
+pub fn day10(rng: &mut StdRng) -> String {
+    let (mut cycles, mut x, mut ops) = (0, 1, vec![]);
+    while cycles < 240 {
+        if cycles == 239 || rng.gen_bool(0.3) {
+            ops.push("noop".to_string());
+            cycles += 1;
+        } else {
+            // keep the sprite roughly on screen
+            let v = rng.gen_range(-10..=10) + if x < 5 { 5 } else if x > 35 { -5 } else { 0 };
+            x += v;
+            ops.push(format!("addx {}", v));
+            cycles += 2;
+        }
+    }
+    join(ops)
+}
+
+pub fn day11(rng: &mut StdRng, monkeys: usize, max_items: usize) -> String {
+    let mut primes = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+    primes.shuffle(rng);
+    let monkeys = monkeys.clamp(2, primes.len());
+    join((0..monkeys).map(|i| {
+        let items = (0..rng.gen_range(1..=max_items)).map(|_| rng.gen_range(50..100).to_string()).collect::<Vec<_>>();
+        let op = match rng.gen_range(0..6) {
+            0 => "* old".to_string(),
+            1 | 2 => format!("* {}", rng.gen_range(2..20)),
+            _ => format!("+ {}", rng.gen_range(1..9)),
+        };
+        let mut other = || (i + rng.gen_range(1..monkeys)) % monkeys;
+        let (if_true, if_false) = (other(), other());
+        format!(
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = old {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+            i, items.join(", "), op, primes[i], if_true, if_false,
+        )
+    })).trim_end().to_string()
+}
+
+// random terrain with a climbable monotone path from S in the top left to E in the bottom right,
+// needs width + height > 27 to fit the climb
+pub fn day12(rng: &mut StdRng, width: usize, height: usize) -> String {
+    let mut grid = (0..height)
+        .map(|_| (0..width).map(|_| (b'a' + rng.gen_range(0..26)) as char).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let steps = width + height - 2;
+    let (mut x, mut y, mut h) = (0, 0, 0u8);
+    for step in 0..steps {
+        grid[y][x] = (b'a' + h) as char;
+        if x + 1 < width && (y + 1 == height || rng.gen_bool(0.5)) {
+            x += 1;
+        } else {
+            y += 1;
+        }
+        // climb when the remaining steps are needed to reach z
+        let left = steps - step - 1;
+        if h < 25 && (usize::from(25 - h) > left || rng.gen_bool(0.6)) {
+            h += 1;
+        }
+    }
+    grid[0][0] = 'S';
+    grid[height - 1][width - 1] = 'E';
+    join(grid.into_iter().map(|row| row.into_iter().collect()))
+}
+
+pub fn day13(rng: &mut StdRng, pairs: usize, depth: usize) -> String {
+    fn packet(rng: &mut StdRng, depth: usize) -> String {
+        let items = (0..rng.gen_range(0..5)).map(|_| {
+            if depth > 0 && rng.gen_bool(0.3) { packet(rng, depth - 1) } else { rng.gen_range(0..=10).to_string() }
+        }).collect::<Vec<_>>();
+        format!("[{}]", items.join(","))
+    }
+    (0..pairs).map(|_| format!("{}\n{}\n", packet(rng, depth), packet(rng, depth))).collect::<Vec<_>>().join("\n").trim_end().to_string()
+}
+
+// rock paths of horizontal and vertical segments below the sand source at 500,0, the first one
+// starts right below it so some sand comes to rest
+pub fn day14(rng: &mut StdRng, paths: usize, width: i64, depth: i64) -> String {
+    join((0..paths).map(|i| {
+        let x = if i == 0 { 500 + rng.gen_range(-4..=0) } else { 500 + rng.gen_range(-width..=width) };
+        let mut p = (x, rng.gen_range(2..depth.max(3)));
+        let mut points = vec![p];
+        for j in 0..rng.gen_range(1..6) {
+            let len = if i == 0 && j == 0 { rng.gen_range(5..=8) } else { rng.gen_range(-8..=8) };
+            if j % 2 == 0 {
+                p.0 += len;
+            } else {
+                p.1 = (p.1 + len).clamp(2, depth.max(3));
+            }
+            points.push(p);
+        }
+        points.iter().map(|(x, y)| format!("{},{}", x, y)).collect::<Vec<_>>().join(" -> ")
+    }))
+}
+
+// Sensors whose ranges cover all of 1..=roi squared but one point. In u = x + y, v = x - y every
+// range is a square, a grid of squares with a spacing of twice their radius covers the plane
+// and the square around the gap is replaced by four that leave out only its center.
+pub fn day15(rng: &mut StdRng, roi: i64, sensors: usize) -> String {
+    let gap = (rng.gen_range(1..=roi), rng.gen_range(1..=roi));
+    let (ug, vg) = (gap.0 + gap.1, gap.0 - gap.1);
+    let tiles = (sensors.max(1) as f64).sqrt().ceil() as i64;
+    // odd radius keeps the centers of the four squares around the gap on the integer grid
+    let r = (roi / tiles).max(1) | 1;
+    let mut ranges = vec![(ug + 1 + r, vg, r), (ug - 1 - r, vg, r), (ug, vg + 1 + r, r), (ug, vg - 1 - r, r)];
+    // tiles whose square reaches into from..=to
+    let steps = |from: i64, to: i64, center: i64| -(center - from + r).div_euclid(2 * r)..=(to - center + r).div_euclid(2 * r);
+    for i in steps(0, 2 * roi, ug) {
+        for j in steps(-roi, roi, vg) {
+            if (i, j) != (0, 0) {
+                // a larger radius still leaves the gap outside as long as it stays below 2r
+                ranges.push((ug + 2 * r * i, vg + 2 * r * j, r + rng.gen_range(0..=r / 4)));
+            }
+        }
+    }
+    ranges.shuffle(rng);
+    join(ranges.into_iter().map(|(u, v, d)| {
+        let (x, y) = ((u + v) / 2, (u - v) / 2);
+        let dx = rng.gen_range(-d..=d);
+        let dy = (d - dx.abs()) * if rng.gen_bool(0.5) { 1 } else { -1 };
+        format!("Sensor at x={}, y={}: closest beacon is at x={}, y={}", x, y, x + dx, y + dy)
+    }))
+}
+
+// connected tunnel graph starting at AA, only flow_valves of the valves release pressure
+pub fn day16(rng: &mut StdRng, valves: usize, flow_valves: usize) -> String {
+    let valves = valves.max(flow_valves + 1);
+    let mut names = HashSet::new();
+    names.insert("AA".to_string());
+    while names.len() < valves {
+        names.insert(format!("{}{}", rng.gen_range('A'..='Z'), rng.gen_range('A'..='Z')));
+    }
+    let mut names = names.into_iter().filter(|n| n != "AA").collect::<Vec<_>>();
+    names.sort();
+    names.shuffle(rng);
+    names.insert(0, "AA".to_string());
+    let mut edges = vec![HashSet::new(); valves];
+    for i in 1..valves {
+        let j = rng.gen_range(0..i);
+        edges[i].insert(j);
+        edges[j].insert(i);
+    }
+    for _ in 0..valves / 2 {
+        let (i, j) = (rng.gen_range(0..valves), rng.gen_range(0..valves));
+        if i != j {
+            edges[i].insert(j);
+            edges[j].insert(i);
+        }
+    }
+    let mut flowing = (1..valves).collect::<Vec<_>>();
+    flowing.shuffle(rng);
+    flowing.truncate(flow_valves);
+    join((0..valves).map(|i| {
+        let rate = if flowing.contains(&i) { rng.gen_range(1..25) } else { 0 };
+        let mut to = edges[i].iter().map(|&j| names[j].as_str()).collect::<Vec<_>>();
+        to.sort();
+        to.shuffle(rng);
+        if to.len() == 1 {
+            format!("Valve {} has flow rate={}; tunnel leads to valve {}", names[i], rate, to[0])
+        } else {
+            format!("Valve {} has flow rate={}; tunnels lead to valves {}", names[i], rate, to.join(", "))
+        }
+    }))
+}
+
+pub fn day17(rng: &mut StdRng, jets: usize) -> String {
+    (0..jets.max(1)).map(|_| if rng.gen_bool(0.5) { '<' } else { '>' }).collect()
+}
+
+pub fn day19(rng: &mut StdRng, blueprints: usize) -> String {
+    join((1..=blueprints).map(|id| {
+        format!(
+            "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.",
+            id, rng.gen_range(2..=4), rng.gen_range(2..=4), rng.gen_range(2..=4), rng.gen_range(5..=20), rng.gen_range(2..=4), rng.gen_range(5..=20),
+        )
+    }))
+}
+
+// exactly one zero
+pub fn day20(rng: &mut StdRng, numbers: usize) -> String {
+    let mut numbers = (1..numbers.max(1)).map(|_| {
+        let n: i64 = rng.gen_range(1..10_000);
+        if rng.gen_bool(0.5) { -n } else { n }
+    }).collect::<Vec<_>>();
+    numbers.insert(rng.gen_range(0..=numbers.len()), 0);
+    join(numbers.into_iter().map(|n| n.to_string()))
+}
+
+// Expression tree built top down from the value every monkey has to yell, so all divisions are
+// exact. Along the path to humn only +, - and * are used, which keeps part1 exact for the
+// arbitrary humn value and part2 solvable by inverting the operations.
+pub fn day21(rng: &mut StdRng, monkeys: usize) -> String {
+    struct Builder<'a> {
+        rng: &'a mut StdRng,
+        names: HashSet<String>,
+        lines: Vec<String>,
+    }
+
+    impl Builder<'_> {
+        fn name(&mut self) -> String {
+            loop {
+                let name = (0..4).map(|_| self.rng.gen_range('a'..='z')).collect::<String>();
+                if name != "root" && name != "humn" && self.names.insert(name.clone()) {
+                    return name;
+                }
+            }
+        }
+
+        // monkey yelling value with about size monkeys below it, the humn leaf if humn is set
+        fn build(&mut self, name: String, value: i64, size: usize, humn: bool) {
+            if humn && size <= 1 {
+                self.lines.push(format!("humn: {}", self.rng.gen_range(1..5000)));
+                return;
+            }
+            if !humn && size <= 1 && value > 0 {
+                self.lines.push(format!("{}: {}", name, value));
+                return;
+            }
+            // leaves have to be positive, a subtraction can turn any value into two of them
+            let op = if size <= 1 { 1 } else { self.rng.gen_range(0..4) };
+            let divisors = (2..=if humn { 5 } else { 20 }).filter(|d| value != 0 && value % d == 0).collect::<Vec<_>>();
+            let (l, op, r) = match op {
+                0 if value > 1 => {
+                    let a = self.rng.gen_range(1..value);
+                    (a, '+', value - a)
+                }
+                2 if !divisors.is_empty() => {
+                    let d = *divisors.choose(self.rng).unwrap();
+                    (value / d, '*', d)
+                }
+                3 if !humn && value > 0 && value < 1_000_000_000 => {
+                    let d = self.rng.gen_range(2..10);
+                    (value * d, '/', d)
+                }
+                _ => {
+                    let r = self.rng.gen_range(1..100) + (-value).max(0);
+                    (value + r, '-', r)
+                }
+            };
+            let rest = size.saturating_sub(1);
+            let left_size = self.rng.gen_range(0..=rest);
+            // humn only ever gets multiplied by the small factor on the right, so a wrong humn
+            // value in part1 can't overflow
+            let humn_left = humn && (op == '*' || self.rng.gen_bool(0.5));
+            let humn_right = humn && !humn_left;
+            let (left, right) = (self.child(humn_left, left_size), self.child(humn_right, rest - left_size));
+            self.lines.push(format!("{}: {} {} {}", name, left, op, right));
+            self.build(left, l, left_size, humn_left);
+            self.build(right, r, rest - left_size, humn_right);
+        }
+
+        fn child(&mut self, humn: bool, size: usize) -> String {
+            if humn && size <= 1 { "humn".to_string() } else { self.name() }
+        }
+    }
+
+    let mut builder = Builder { rng, names: HashSet::new(), lines: vec![] };
+    let value = builder.rng.gen_range(1000..1_000_000);
+    let humn_left = builder.rng.gen_bool(0.5);
+    let half = monkeys.saturating_sub(1) / 2;
+    let (a, b) = (builder.child(humn_left, half), builder.child(!humn_left, half));
+    builder.lines.push(format!("root: {} + {}", a, b));
+    builder.build(a, value, half, humn_left);
+    builder.build(b, value, half, !humn_left);
+    let mut lines = builder.lines;
+    lines.shuffle(builder.rng);
+    join(lines)
+}
+
+// one of the eleven cube nets, rotated or mirrored at random, with size x size faces
+pub fn day22(rng: &mut StdRng, size: usize, instructions: usize) -> String {
+    const NETS: [&[&str]; 11] = [
+        &["#...", "####", "#..."],
+        &["#...", "####", ".#.."],
+        &["#...", "####", "..#."],
+        &["#...", "####", "...#"],
+        &[".#..", "####", ".#.."],
+        &[".#..", "####", "..#."],
+        &["##..", ".###", ".#.."],
+        &["##..", ".###", "..#."],
+        &["##..", ".###", "...#"],
+        &["##..", ".##.", "..##"],
+        &["###..", "..###"],
+    ];
+    let mut net = NETS.choose(rng).unwrap().iter().map(|r| r.chars().map(|c| c == '#').collect::<Vec<_>>()).collect::<Vec<_>>();
+    for _ in 0..rng.gen_range(0..4) {
+        // rotate clockwise
+        net = (0..net[0].len()).map(|x| (0..net.len()).rev().map(|y| net[y][x]).collect()).collect();
+    }
+    if rng.gen_bool(0.5) {
+        net.iter_mut().for_each(|row| row.reverse());
+    }
+    let mut lines = (0..net.len() * size).map(|y| {
+        let row = (0..net[0].len() * size).map(|x| {
+            match net[y / size][x / size] {
+                false => ' ',
+                true if rng.gen_bool(0.15) => '#',
+                true => '.',
+            }
+        }).collect::<String>();
+        row.trim_end().to_string()
+    }).collect::<Vec<_>>();
+    // the walk starts on the leftmost tile of the top row, it must not be a wall
+    let start = lines[0].find(['.', '#']).unwrap();
+    lines[0].replace_range(start..=start, ".");
+    let mut path = rng.gen_range(1..2 * size).to_string();
+    for _ in 0..instructions {
+        path.push(if rng.gen_bool(0.5) { 'L' } else { 'R' });
+        path += &rng.gen_range(1..2 * size).to_string();
+    }
+    format!("{}\n\n{}", join(lines), path)
+}
+
+pub fn day23(rng: &mut StdRng, size: usize, density_percent: u32) -> String {
+    join((0..size).map(|_| (0..size).map(|_| if rng.gen_ratio(density_percent.min(100), 100) { '#' } else { '.' }).collect()))
+}
+
+const DAY24_ATTEMPTS: usize = 32;
+
+// valley with walls, the entry in the top and the exit in the bottom row, no vertical blizzards
+// in their columns
+// Dense blizzards can wall the expedition in for good, such valleys are drawn again a limited
+// number of times. With a single row between the walls vertical blizzards never move out of the
+// way, so small and dense valleys often fail.
+pub fn day24(rng: &mut StdRng, width: usize, height: usize, blizzard_percent: u32) -> Result<String, String> {
+    for _ in 0..DAY24_ATTEMPTS {
+        let valley = day24_valley(rng, width, height, blizzard_percent);
+        if day24::part1(&day24::generator(&valley)).is_ok() {
+            return Ok(valley);
+        }
+    }
+    Err(format!("no passable {}x{} valley with {}% blizzards in {} attempts", width, height, blizzard_percent, DAY24_ATTEMPTS))
+}
+
+fn day24_valley(rng: &mut StdRng, width: usize, height: usize, blizzard_percent: u32) -> String {
+    let (width, height) = (width.max(3), height.max(3));
+    join((0..height).map(|y| (0..width).map(|x| {
+        if y == 0 {
+            if x == 1 { '.' } else { '#' }
+        } else if y == height - 1 {
+            if x == width - 2 { '.' } else { '#' }
+        } else if x == 0 || x == width - 1 {
+            '#'
+        } else if rng.gen_ratio(blizzard_percent.min(100), 100) {
+            let vertical = x != 1 && x != width - 2;
+            let choices: &[char] = if vertical { &['<', '>', '^', 'v'] } else { &['<', '>'] };
+            *choices.choose(rng).unwrap()
+        } else {
+            '.'
+        }
+    }).collect()))
+}
+
+pub fn day25(rng: &mut StdRng, numbers: usize) -> String {
+    join((0..numbers).map(|_| Snafu::from(rng.gen_range(1..1_000_000_000_000i64)).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc::Point;
+    use crate::{day15, day21, registry};
+
+    #[test]
+    pub fn test_deterministic() {
+        for day in 1..=25 {
+            assert_eq!(generate(day, 7, 10), generate(day, 7, 10));
+        }
+        assert_ne!(generate(8, 1, 10), generate(8, 2, 10));
+        assert_eq!(generate(26, 1, 10), None);
+    }
+
+    #[test]
+    pub fn test_all_days() {
+        let entries = registry();
+        // day15 is only built for the full size, see test_day15, and day18 isn't solved yet
+        assert_eq!(generate(18, 0, 6), None);
+        for e in entries.iter().filter(|e| e.variant.is_none() && e.day != 15 && e.day != 18) {
+            for seed in 0..3 {
+                let input = generate(e.day, seed, 6).unwrap();
+                assert!(e.solve(&input).is_ok(), "{} seed {}\n{}", e.name(), seed, input);
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_day15() {
+        for seed in 0..10 {
+            let input = day15(&mut rng(seed), 40, 1 + seed as usize);
+            let data = day15::generator(&input);
+            let uncovered = (1..=40).flat_map(|x| (1..=40).map(move |y| Point::new(x, y)))
+                .filter(|p| data.0.iter().all(|(s, d)| s.manhattan(*p) > *d))
+                .collect::<Vec<_>>();
+            assert_eq!(uncovered.len(), 1, "seed {}", seed);
+            assert_eq!(day15::solve_part2(&data, 40), uncovered[0].x * 4000000 + uncovered[0].y);
+        }
+    }
+
+    #[test]
+    pub fn test_day24() {
+        for seed in 0..5 {
+            let valley = day24(&mut rng(seed), 18, 6, 60).unwrap();
+            assert!(day24::part1(&day24::generator(&valley)).is_ok(), "seed {}\n{}", seed, valley);
+        }
+        // every cell between the walls is a blizzard, nothing gets through
+        assert!(day24(&mut rng(0), 3, 3, 100).is_err());
+        assert!(day24(&mut rng(0), 12, 5, 100).is_err());
+    }
+
+    #[test]
+    pub fn test_day21() {
+        for seed in 0..20 {
+            let input = day21(&mut rng(seed), 5 + 10 * seed as usize);
+            let humn = day21::part2(&day21::generator(&input));
+            // yelling the part2 answer makes both sides of root equal
+            let input = input.lines().map(|l| {
+                match &l[..4] {
+                    "humn" => format!("humn: {}", humn),
+                    "root" => l.replace('+', "-"),
+                    _ => l.to_string(),
+                }
+            }).collect::<Vec<_>>().join("\n");
+            assert_eq!(day21::part1(&day21::generator(&input)), 0, "seed {}", seed);
+        }
+    }
+}
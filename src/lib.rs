@@ -1,12 +1,19 @@
-extern crate aoc_runner;
-
-#[macro_use]
-extern crate aoc_runner_derive;
 extern crate regex;
 extern crate statistical;
 extern crate aoc;
 
-pub mod help;
+pub mod solution;
+pub mod alloc_count;
+pub mod cancel;
+pub mod runner;
+pub mod differential;
+pub mod inputgen;
+pub mod report;
+
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static ALLOCATOR: alloc_count::Counting = alloc_count::Counting;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -24,6 +31,7 @@ pub mod day14;
 pub mod day15;
 pub mod day16;
 pub mod day17;
+pub mod day18;
 pub mod day19;
 pub mod day20;
 pub mod day21;
@@ -32,6 +40,35 @@ pub mod day23;
 pub mod day24;
 pub mod day25;
 
-aoc_lib!{ year = 2022 }
-
+use solution::{Entry, Solution};
 
+// every day and variant, in order
+pub fn registry() -> Vec<Entry> {
+    [
+        day01::Day01::entries(),
+        day02::Day02::entries(),
+        day03::Day03::entries(),
+        day04::Day04::entries(),
+        day05::Day05::entries(),
+        day06::Day06::entries(),
+        day07::Day07::entries(),
+        day08::Day08::entries(),
+        day09::Day09::entries(),
+        day10::Day10::entries(),
+        day11::Day11::entries(),
+        day12::Day12::entries(),
+        day13::Day13::entries(),
+        day14::Day14::entries(),
+        day15::Day15::entries(),
+        day16::Day16::entries(),
+        day17::Day17::entries(),
+        day18::Day18::entries(),
+        day19::Day19::entries(),
+        day20::Day20::entries(),
+        day21::Day21::entries(),
+        day22::Day22::entries(),
+        day23::Day23::entries(),
+        day24::Day24::entries(),
+        day25::Day25::entries(),
+    ].into_iter().flatten().collect()
+}
@@ -1,7 +1,118 @@
 extern crate aoc_2022_rust;
-extern crate aoc_runner_derive;
-extern crate aoc_runner;
 
-use aoc_runner_derive::aoc_main;
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
-aoc_main! { lib = aoc_2022_rust }
\ No newline at end of file
+use aoc_2022_rust::registry;
+use aoc_2022_rust::report::{self, Format, Status};
+use aoc_2022_rust::runner::{self, Job, Options};
+use aoc_2022_rust::solution::{answers_path, input_path};
+
+const USAGE: &str = "usage: aoc_2022_rust [--list] [--format text|json|csv|markdown] [--answers FILE]
+                     [--threads N] [--timeout SECONDS] [DAY [PART]]";
+
+fn main() -> ExitCode {
+    let mut list = false;
+    let mut format = Format::Text;
+    let mut answers_file = answers_path();
+    // either of --threads and --timeout switches to the threaded runner
+    let mut pool = None;
+    let mut numbers = vec![];
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let ok = match arg.as_str() {
+            "--list" => {
+                list = true;
+                true
+            }
+            "--format" => args.next().and_then(|f| f.parse().ok()).map(|f| format = f).is_some(),
+            "--answers" => args.next().map(|f| answers_file = PathBuf::from(f)).is_some(),
+            "--threads" => args.next().and_then(|n| n.parse().ok())
+                .map(|n| pool.get_or_insert(Options::default()).threads = n).is_some(),
+            "--timeout" => args.next().and_then(|t| t.parse().ok()).and_then(|t| Duration::try_from_secs_f64(t).ok())
+                .map(|t| pool.get_or_insert(Options::default()).timeout = Some(t)).is_some(),
+            _ => arg.parse::<u32>().map(|n| numbers.push(n)).is_ok(),
+        };
+        if !ok {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    }
+    let (day, part) = match numbers[..] {
+        [] => (None, None),
+        [d] => (Some(d), None),
+        [d, p] => (Some(d), Some(p as u8)),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let entries = registry().into_iter().filter(|e| e.matches(day, part)).collect::<Vec<_>>();
+    if list {
+        for e in entries {
+            println!("{} - {}", e.name(), e.title);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let answers = match report::read_answers(&answers_file) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("can not read answers: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    // panics end up in the report, the default hook would only garble it
+    if format != Format::Text {
+        panic::set_hook(Box::new(|_| {}));
+    }
+
+    let mut failed = false;
+    let mut records = vec![];
+    let mut jobs = vec![];
+    for e in entries {
+        let input = match fs::read_to_string(input_path(e.day)) {
+            Ok(input) => input,
+            // without an explicit day, days without input are skipped quietly
+            Err(_) if day.is_none() => continue,
+            Err(err) => {
+                eprintln!("{}: can not read {}: {}", e.name(), input_path(e.day).display(), err);
+                failed = true;
+                continue;
+            }
+        };
+        if pool.is_some() {
+            jobs.push(Job { entry: e, input });
+            continue;
+        }
+        let record = report::measure(&e, &input, &answers);
+        failed |= record.status != Status::Ok;
+        // text goes out as it comes, the other formats need all records
+        if format == Format::Text {
+            print!("{}", report::text(&[record]));
+        } else {
+            records.push(record);
+        }
+    }
+    if let Some(options) = pool {
+        let start = Instant::now();
+        records = match runner::run(jobs, &answers, &options) {
+            Ok(records) => records,
+            Err(err) => {
+                eprintln!("can not start threads: {}", err);
+                return ExitCode::FAILURE;
+            }
+        };
+        failed |= records.iter().any(|r| r.status != Status::Ok);
+        eprintln!("total: {:?} on {} threads", start.elapsed(), options.threads());
+    }
+    if format != Format::Text || pool.is_some() {
+        print!("{}", report::render(&records, format));
+    }
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
@@ -0,0 +1,411 @@
+// Machine readable results of a run, one record per day, part and variant.
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::alloc_count::Stats;
+use crate::solution::{Answer, Entry, Run};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error,
+    Panic,
+    Mismatch,
+    Timeout,
+}
+
+impl Status {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Error => "error",
+            Status::Panic => "panic",
+            Status::Mismatch => "mismatch",
+            Status::Timeout => "timeout",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u32,
+    pub part: u8,
+    pub variant: Option<&'static str>,
+    pub title: &'static str,
+    pub answer: Answer,
+    pub expected: Option<String>,
+    pub parse: Duration,
+    pub solve: Duration,
+    // peak resident set size of the whole process in bytes, None where /proc is missing
+    pub peak_memory: Option<u64>,
+    // only with the count-alloc feature
+    pub parse_alloc: Option<Stats>,
+    pub solve_alloc: Option<Stats>,
+    pub status: Status,
+}
+
+impl Record {
+    pub fn new(entry: &Entry, run: Run, expected: Option<String>, peak_memory: Option<u64>) -> Record {
+        let status = match (&run.answer, &expected) {
+            _ if run.panicked => Status::Panic,
+            (Err(_), _) => Status::Error,
+            (Ok(a), Some(e)) if a != e => Status::Mismatch,
+            _ => Status::Ok,
+        };
+        Record {
+            day: entry.day,
+            part: entry.part,
+            variant: entry.variant,
+            title: entry.title,
+            answer: run.answer,
+            expected,
+            parse: run.metrics.parse,
+            solve: run.metrics.solve,
+            peak_memory,
+            parse_alloc: run.metrics.parse_alloc,
+            solve_alloc: run.metrics.solve_alloc,
+            status,
+        }
+    }
+
+    // for solutions the runner gave up on, they may still be running
+    pub fn timed_out(entry: &Entry, expected: Option<String>, timeout: Duration) -> Record {
+        Record {
+            day: entry.day,
+            part: entry.part,
+            variant: entry.variant,
+            title: entry.title,
+            answer: Err(format!("timed out after {:?}", timeout)),
+            expected,
+            parse: Duration::ZERO,
+            solve: timeout,
+            peak_memory: None,
+            parse_alloc: None,
+            solve_alloc: None,
+            status: Status::Timeout,
+        }
+    }
+
+    // parse and solve together
+    pub fn alloc(&self) -> Option<Stats> {
+        match (self.parse_alloc, self.solve_alloc) {
+            (Some(p), Some(s)) => Some(p.then(s)),
+            (p, s) => p.or(s),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self.variant {
+            Some(v) => format!("day{:02} part{} ({})", self.day, self.part, v),
+            None => format!("day{:02} part{}", self.day, self.part),
+        }
+    }
+}
+
+// runs one entry and measures it, the peak is reset first so earlier days don't leak into it
+pub fn measure(entry: &Entry, input: &str, answers: &Answers) -> Record {
+    reset_peak_memory();
+    let run = entry.run(input);
+    let peak = peak_memory();
+    Record::new(entry, run, answers.get(&(entry.day, entry.part)).cloned(), peak)
+}
+
+pub fn reset_peak_memory() {
+    // 5 resets the high water mark, not available on every kernel
+    let _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+pub fn peak_memory() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+pub type Answers = HashMap<(u32, u8), String>;
+
+// lines of "DAY PART ANSWER", multi line answers write their line breaks as \n, # starts a comment
+pub fn parse_answers(text: &str) -> Result<Answers, String> {
+    let mut answers = Answers::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.splitn(3, ' ');
+        let (day, part, answer) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(d), Some(p), Some(a)) => (d.parse::<u32>(), p.parse::<u8>(), a),
+            _ => return Err(format!("line {}: expected DAY PART ANSWER", i + 1)),
+        };
+        match (day, part) {
+            (Ok(day), Ok(part)) => answers.insert((day, part), answer.replace("\\n", "\n")),
+            _ => return Err(format!("line {}: invalid day or part", i + 1)),
+        };
+    }
+    Ok(answers)
+}
+
+// a missing file just means nothing is known yet
+pub fn read_answers(path: &Path) -> io::Result<Answers> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse_answers(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::new()),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(format!("unknown format: {:?}", s)),
+        }
+    }
+}
+
+pub fn render(records: &[Record], format: Format) -> String {
+    match format {
+        Format::Text => text(records),
+        Format::Json => json(records),
+        Format::Csv => csv(records),
+        Format::Markdown => markdown(records),
+    }
+}
+
+// what the runner always printed
+pub fn text(records: &[Record]) -> String {
+    let mut out = String::new();
+    for r in records {
+        match (&r.answer, r.status) {
+            (Ok(a), Status::Mismatch) => writeln!(out, "{} : {} (expected {})\n\ttime: {:?}", r.name(), a, r.expected.as_deref().unwrap_or(""), r.parse + r.solve),
+            (Ok(a), _) => writeln!(out, "{} : {}\n\ttime: {:?}", r.name(), a, r.parse + r.solve),
+            (Err(e), _) => writeln!(out, "{} failed: {}", r.name(), e),
+        }.unwrap();
+    }
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_option<T>(v: Option<T>, f: impl Fn(T) -> String) -> String {
+    v.map(f).unwrap_or_else(|| "null".to_string())
+}
+
+fn json_alloc(a: Stats) -> String {
+    format!("{{\"allocations\": {}, \"bytes\": {}, \"peak_bytes\": {}}}", a.allocations, a.bytes, a.peak)
+}
+
+pub fn json(records: &[Record]) -> String {
+    let rows = records.iter().map(|r| {
+        format!(
+            "  {{\"day\": {}, \"part\": {}, \"variant\": {}, \"title\": {}, \"status\": {}, \"answer\": {}, \"error\": {}, \"expected\": {}, \"parse_ns\": {}, \"solve_ns\": {}, \"peak_memory_bytes\": {}, \"parse_alloc\": {}, \"solve_alloc\": {}}}",
+            r.day,
+            r.part,
+            json_option(r.variant, json_string),
+            json_string(r.title),
+            json_string(r.status.as_str()),
+            json_option(r.answer.as_ref().ok(), |a| json_string(a)),
+            json_option(r.answer.as_ref().err(), |e| json_string(e)),
+            json_option(r.expected.as_deref(), json_string),
+            r.parse.as_nanos(),
+            r.solve.as_nanos(),
+            json_option(r.peak_memory, |m| m.to_string()),
+            json_option(r.parse_alloc, json_alloc),
+            json_option(r.solve_alloc, json_alloc),
+        )
+    }).collect::<Vec<_>>();
+    if rows.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", rows.join(",\n"))
+    }
+}
+
+fn csv_alloc(a: Option<Stats>) -> String {
+    a.map(|a| format!("{},{},{}", a.allocations, a.bytes, a.peak)).unwrap_or_else(|| ",,".to_string())
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn csv(records: &[Record]) -> String {
+    let mut out = String::from("day,part,variant,title,status,answer,expected,parse_ns,solve_ns,peak_memory_bytes,\
+        parse_allocations,parse_alloc_bytes,parse_peak_heap_bytes,solve_allocations,solve_alloc_bytes,solve_peak_heap_bytes\n");
+    for r in records {
+        let answer = match &r.answer {
+            Ok(a) => a.clone(),
+            Err(e) => e.clone(),
+        };
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            r.day,
+            r.part,
+            csv_field(r.variant.unwrap_or("")),
+            csv_field(r.title),
+            r.status.as_str(),
+            csv_field(&answer),
+            csv_field(r.expected.as_deref().unwrap_or("")),
+            r.parse.as_nanos(),
+            r.solve.as_nanos(),
+            r.peak_memory.map(|m| m.to_string()).unwrap_or_default(),
+            csv_alloc(r.parse_alloc),
+            csv_alloc(r.solve_alloc),
+        ).unwrap();
+    }
+    out
+}
+
+fn mib(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', "<br>")
+}
+
+pub fn markdown(records: &[Record]) -> String {
+    let mut out = String::from("| Day | Part | Variant | Title | Status | Answer | Parse | Solve | Peak memory | Allocations | Peak heap |\n");
+    out.push_str("|---:|---:|---|---|---|---|---:|---:|---:|---:|---:|\n");
+    for r in records {
+        let answer = match &r.answer {
+            Ok(a) => markdown_cell(a),
+            Err(e) => format!("error: {}", markdown_cell(e)),
+        };
+        let memory = r.peak_memory.map(mib).unwrap_or_default();
+        let alloc = r.alloc();
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {:?} | {:?} | {} | {} | {} |",
+            r.day,
+            r.part,
+            markdown_cell(r.variant.unwrap_or("")),
+            markdown_cell(r.title),
+            r.status.as_str(),
+            answer,
+            r.parse,
+            r.solve,
+            memory,
+            alloc.map(|a| a.allocations.to_string()).unwrap_or_default(),
+            alloc.map(|a| mib(a.peak)).unwrap_or_default(),
+        ).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn record(answer: Answer, status: Status) -> Record {
+        Record {
+            day: 5,
+            part: 1,
+            variant: None,
+            title: "Supply Stacks",
+            answer,
+            expected: None,
+            parse: Duration::from_nanos(1500),
+            solve: Duration::from_micros(20),
+            peak_memory: Some(2 * 1024 * 1024),
+            parse_alloc: Some(Stats { allocations: 3, bytes: 300, peak: 200 }),
+            solve_alloc: None,
+            status,
+        }
+    }
+
+    #[test]
+    pub fn test_status() {
+        let entries = registry();
+        let entry = entries.iter().find(|e| e.matches(Some(1), Some(1)) && e.variant.is_none()).unwrap();
+        let mut answers = Answers::new();
+        assert_eq!(measure(entry, "1\n2\n\n4", &answers).status, Status::Ok);
+        answers.insert((1, 1), "4".to_string());
+        assert_eq!(measure(entry, "1\n2\n\n4", &answers).status, Status::Ok);
+        answers.insert((1, 1), "5".to_string());
+        assert_eq!(measure(entry, "1\n2\n\n4", &answers).status, Status::Mismatch);
+        assert_eq!(measure(entry, "x", &answers).status, Status::Panic);
+        let entry = entries.iter().find(|e| e.matches(Some(2), Some(1)) && e.variant.is_none()).unwrap();
+        assert_eq!(measure(entry, "A Q", &answers).status, Status::Error);
+    }
+
+    #[test]
+    pub fn test_answers() {
+        let answers = parse_answers("# day part answer\n1 1 24000\n\n10 2 ##..\\n#..#\n").unwrap();
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[&(1, 1)], "24000");
+        assert_eq!(answers[&(10, 2)], "##..\n#..#");
+        assert!(parse_answers("1 1").is_err());
+        assert!(parse_answers("a 1 5").is_err());
+    }
+
+    #[test]
+    pub fn test_formats() {
+        assert_eq!("md".parse::<Format>(), Ok(Format::Markdown));
+        assert!("xml".parse::<Format>().is_err());
+        let records = vec![record(Ok("C\"M,Z".to_string()), Status::Ok), record(Err("bad\nmove".to_string()), Status::Error)];
+        assert_eq!(
+            json(&records),
+            "[\n  {\"day\": 5, \"part\": 1, \"variant\": null, \"title\": \"Supply Stacks\", \"status\": \"ok\", \"answer\": \"C\\\"M,Z\", \"error\": null, \"expected\": null, \"parse_ns\": 1500, \"solve_ns\": 20000, \"peak_memory_bytes\": 2097152, \"parse_alloc\": {\"allocations\": 3, \"bytes\": 300, \"peak_bytes\": 200}, \"solve_alloc\": null},\n  \
+             {\"day\": 5, \"part\": 1, \"variant\": null, \"title\": \"Supply Stacks\", \"status\": \"error\", \"answer\": null, \"error\": \"bad\\nmove\", \"expected\": null, \"parse_ns\": 1500, \"solve_ns\": 20000, \"peak_memory_bytes\": 2097152, \"parse_alloc\": {\"allocations\": 3, \"bytes\": 300, \"peak_bytes\": 200}, \"solve_alloc\": null}\n]\n"
+        );
+        assert_eq!(json(&[]), "[]\n");
+        assert_eq!(
+            csv(&records),
+            "day,part,variant,title,status,answer,expected,parse_ns,solve_ns,peak_memory_bytes,\
+             parse_allocations,parse_alloc_bytes,parse_peak_heap_bytes,solve_allocations,solve_alloc_bytes,solve_peak_heap_bytes\n\
+             5,1,,Supply Stacks,ok,\"C\"\"M,Z\",,1500,20000,2097152,3,300,200,,,\n\
+             5,1,,Supply Stacks,error,\"bad\nmove\",,1500,20000,2097152,3,300,200,,,\n"
+        );
+        let md = markdown(&records);
+        assert_eq!(md.lines().count(), 4);
+        assert_eq!(md.lines().nth(3), Some("| 5 | 1 |  | Supply Stacks | error | error: bad<br>move | 1.5µs | 20µs | 2.0 MiB | 3 | 0.0 MiB |"));
+    }
+
+    #[test]
+    pub fn test_peak_memory() {
+        if let Some(before) = peak_memory() {
+            let big = std::hint::black_box(vec![1u8; 64 << 20]);
+            assert!(peak_memory().unwrap() >= before.max(big.len() as u64));
+        }
+    }
+}
@@ -0,0 +1,202 @@
+// Runs solutions on their own threads, at most Options::threads at a time, gives up on the ones
+// that exceed their wall-clock timeout and cancels them. A job that ignores its token for longer
+// than GRACE keeps its thread but not its slot, so the jobs after it still run. Records come back
+// in the order of the jobs, so day order for the registry.
+use std::collections::HashMap;
+use std::io;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cancel::Cancel;
+use crate::report::{self, Answers, Record};
+use crate::solution::Entry;
+
+// how long a cancelled job keeps its slot to reach a checkpoint and report back
+const GRACE: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+    // 0 picks one per core
+    pub threads: usize,
+    pub timeout: Option<Duration>,
+}
+
+impl Options {
+    pub fn threads(&self) -> usize {
+        match self.threads {
+            0 => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            n => n,
+        }
+    }
+}
+
+pub struct Job {
+    pub entry: Entry,
+    pub input: String,
+}
+
+pub fn run(jobs: Vec<Job>, answers: &Answers, options: &Options) -> io::Result<Vec<Record>> {
+    let threads = options.threads();
+    let jobs = jobs.into_iter().map(Arc::new).collect::<Vec<_>>();
+    let cancels = jobs.iter().map(|_| Cancel::new()).collect::<Vec<_>>();
+    let expected = jobs.iter().map(|j| answers.get(&(j.entry.day, j.entry.part)).cloned()).collect::<Vec<_>>();
+
+    let (tx, rx) = mpsc::channel();
+    let mut records = jobs.iter().map(|_| None).collect::<Vec<Option<Record>>>();
+    let mut running = HashMap::new();
+    let mut cancelled = HashMap::new();
+    let mut next = 0;
+    let mut left = jobs.len();
+    // once a job is left behind it may still be running, and the memory high water mark and the
+    // allocation counters belong to the whole process
+    let mut abandoned = false;
+    while left > 0 {
+        while running.len() + cancelled.len() < threads && next < jobs.len() {
+            let i = next;
+            next += 1;
+            let measure = threads == 1 && !abandoned;
+            let (job, cancel, expected, tx) = (jobs[i].clone(), cancels[i].clone(), expected[i].clone(), tx.clone());
+            thread::Builder::new().name(job.entry.name()).spawn(move || {
+                if measure {
+                    report::reset_peak_memory();
+                }
+                let run = cancel.install(|| job.entry.run(&job.input));
+                let peak = if measure { report::peak_memory() } else { None };
+                let mut record = Record::new(&job.entry, run, expected, peak);
+                if !measure {
+                    record.parse_alloc = None;
+                    record.solve_alloc = None;
+                }
+                let _ = tx.send((i, record));
+            })?;
+            running.insert(i, Instant::now());
+        }
+
+        let timeouts = options.timeout.map(|t| running.values().map(move |&start: &Instant| start + t));
+        let graces = cancelled.values().map(|&at: &Instant| at + GRACE);
+        let deadline = timeouts.into_iter().flatten().chain(graces).min();
+        let event = match deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match event {
+            Ok((i, record)) => {
+                if running.remove(&i).is_some() {
+                    records[i] = Some(record);
+                    left -= 1;
+                }
+                // a cancelled job that reports back frees its slot, its timeout record stays
+                cancelled.remove(&i);
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let timeout = options.timeout.unwrap_or_default();
+                let now = Instant::now();
+                let expired = running.iter().filter(|(_, &start)| now >= start + timeout).map(|(&i, _)| i).collect::<Vec<_>>();
+                for i in expired {
+                    running.remove(&i);
+                    cancels[i].cancel();
+                    cancelled.insert(i, now);
+                    records[i] = Some(Record::timed_out(&jobs[i].entry, expected[i].clone(), timeout));
+                    left -= 1;
+                }
+                let before = cancelled.len();
+                cancelled.retain(|_, &mut at| now < at + GRACE);
+                abandoned |= cancelled.len() < before;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    // threads of jobs that ignore their token are detached, they end with the process
+    Ok(records.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use crate::registry;
+    use crate::report::Status;
+    use crate::solution::{Answer, IntoAnswer, Solution};
+
+    fn jobs(inputs: &[(u32, u8, &str)]) -> Vec<Job> {
+        let mut entries = registry().into_iter().filter(|e| e.variant.is_none()).map(Some).collect::<Vec<_>>();
+        inputs.iter().map(|&(day, part, input)| {
+            let i = entries.iter().position(|e| e.as_ref().is_some_and(|e| e.matches(Some(day), Some(part)))).unwrap();
+            Job { entry: entries[i].take().unwrap(), input: input.to_string() }
+        }).collect()
+    }
+
+    #[test]
+    pub fn test_order() {
+        let input = "1\n2\n\n4";
+        let jobs = jobs(&[(1, 1, input), (1, 2, input), (2, 1, "A Y"), (2, 2, "A Q"), (25, 1, "1=\n2")]);
+        let mut answers = Answers::new();
+        answers.insert((2, 1), "9".to_string());
+        let records = run(jobs, &answers, &Options { threads: 3, timeout: None }).unwrap();
+        let summary = records.iter().map(|r| (r.day, r.part, r.status)).collect::<Vec<_>>();
+        assert_eq!(summary, vec![(1, 1, Status::Ok), (1, 2, Status::Ok), (2, 1, Status::Mismatch), (2, 2, Status::Error), (25, 1, Status::Ok)]);
+        assert_eq!(records[0].answer, Ok("4".to_string()));
+        assert!(records.iter().all(|r| r.peak_memory.is_none() && r.alloc().is_none()));
+    }
+
+    #[test]
+    pub fn test_timeout() {
+        // day16 without any flow makes best_routes try every order of 14 valves
+        let valves = (0..15).map(|i| {
+            let tunnels = (0..15).filter(|&j| j != i).map(|j| format!("V{:02}", j)).collect::<Vec<_>>().join(", ");
+            let name = if i == 0 { "AA".to_string() } else { format!("V{:02}", i) };
+            format!("Valve {} has flow rate={}; tunnels lead to valves {}", name, if i == 0 { 0 } else { 1 }, tunnels.replace("V00", "AA"))
+        }).collect::<Vec<_>>().join("\n");
+        let jobs = jobs(&[(16, 1, &valves), (1, 1, "1\n2\n\n4")]);
+        let options = Options { threads: 1, timeout: Some(Duration::from_millis(100)) };
+        let start = Instant::now();
+        let records = run(jobs, &Answers::new(), &options).unwrap();
+        assert_eq!(records.iter().map(|r| r.status).collect::<Vec<_>>(), vec![Status::Timeout, Status::Ok]);
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(records[1].peak_memory.is_some() || report::peak_memory().is_none());
+    }
+
+    static RELEASE: AtomicBool = AtomicBool::new(false);
+
+    // never looks at its cancellation token, only the end of the test lets it go
+    struct Hang;
+
+    impl Solution for Hang {
+        const DAY: u32 = 99;
+        const TITLE: &'static str = "Hang";
+        type Input = ();
+
+        fn parse(_input: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn part1(_input: &()) -> Answer {
+            while !RELEASE.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(10));
+            }
+            "released".into_answer()
+        }
+
+        fn part2(_input: &()) -> Answer {
+            "0".into_answer()
+        }
+    }
+
+    #[test]
+    pub fn test_timeout_uncooperative() {
+        let mut jobs = Hang::entries().into_iter().take(1).map(|entry| Job { entry, input: String::new() }).collect::<Vec<_>>();
+        jobs.extend(self::jobs(&[(1, 1, "1\n2\n\n4"), (2, 1, "A Y")]));
+        let options = Options { threads: 1, timeout: Some(Duration::from_millis(100)) };
+        let start = Instant::now();
+        let records = run(jobs, &Answers::new(), &options).unwrap();
+        let elapsed = start.elapsed();
+        RELEASE.store(true, Ordering::Relaxed);
+        assert_eq!(records.iter().map(|r| r.status).collect::<Vec<_>>(), vec![Status::Timeout, Status::Ok, Status::Ok]);
+        assert_eq!(records[2].answer, Ok("8".to_string()));
+        assert!(elapsed < Duration::from_secs(10));
+        // the hung job may still be running, nothing after it is measured
+        assert!(records[1..].iter().all(|r| r.peak_memory.is_none() && r.alloc().is_none()));
+    }
+}
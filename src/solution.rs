@@ -0,0 +1,220 @@
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::alloc_count::{Scope, Stats};
+use crate::cancel::Cancelled;
+
+// Answers are compared and printed as text, a failing part reports its error instead.
+pub type Answer = Result<String, String>;
+
+pub trait IntoAnswer {
+    fn into_answer(self) -> Answer;
+}
+
+macro_rules! display_answer {
+    ($($t:ty),*) => {
+        $(impl IntoAnswer for $t {
+            fn into_answer(self) -> Answer {
+                Ok(self.to_string())
+            }
+        })*
+    };
+}
+
+display_answer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, String, &str);
+
+impl<T: IntoAnswer, E: Display> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Answer {
+        self.map_err(|e| e.to_string()).and_then(IntoAnswer::into_answer)
+    }
+}
+
+// An alternative implementation of one part, run on the same parsed input.
+pub struct Variant<I> {
+    pub part: u8,
+    pub name: &'static str,
+    pub run: fn(&I) -> Answer,
+}
+
+pub trait Solution {
+    const DAY: u32;
+    const TITLE: &'static str;
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, String>;
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+
+    fn variants() -> Vec<Variant<Self::Input>> {
+        vec![]
+    }
+
+    // both parts followed by the variants, ready to be put into the registry
+    fn entries() -> Vec<Entry>
+        where
+            Self: Sized + 'static,
+            Self::Input: 'static,
+    {
+        let main = [
+            Variant { part: 1, name: "", run: Self::part1 },
+            Variant { part: 2, name: "", run: Self::part2 },
+        ];
+        main.into_iter().chain(Self::variants()).map(|v| {
+            let run = v.run;
+            Entry {
+                day: Self::DAY,
+                title: Self::TITLE,
+                part: v.part,
+                variant: if v.name.is_empty() { None } else { Some(v.name) },
+                prepare: Box::new(move |input| {
+                    let parsed = Self::parse(input)?;
+                    Ok(Box::new(move || run(&parsed)) as Prepared)
+                }),
+            }
+        }).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+    pub parse: Duration,
+    pub solve: Duration,
+    // None unless built with the count-alloc feature
+    pub parse_alloc: Option<Stats>,
+    pub solve_alloc: Option<Stats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub answer: Answer,
+    pub panicked: bool,
+    pub metrics: Metrics,
+}
+
+// a part with its input already parsed, it can be run as often as needed
+pub type Prepared = Box<dyn Fn() -> Answer>;
+
+type Prepare = Box<dyn Fn(&str) -> Result<Prepared, String> + Send + Sync>;
+
+// One runnable part of a day with the parser erased, so all days fit into one list.
+pub struct Entry {
+    pub day: u32,
+    pub title: &'static str,
+    pub part: u8,
+    pub variant: Option<&'static str>,
+    prepare: Prepare,
+}
+
+impl Entry {
+    pub fn solve(&self, input: &str) -> Answer {
+        self.run(input).answer
+    }
+
+    pub fn prepare(&self, input: &str) -> Result<Prepared, String> {
+        (self.prepare)(input)
+    }
+
+    fn measure(&self, input: &str, metrics: &mut Metrics) -> Answer {
+        let (start, scope) = (Instant::now(), Scope::start());
+        let prepared = self.prepare(input);
+        metrics.parse = start.elapsed();
+        metrics.parse_alloc = scope.finish();
+        let (start, scope) = (Instant::now(), Scope::start());
+        let answer = prepared?();
+        metrics.solve = start.elapsed();
+        metrics.solve_alloc = scope.finish();
+        answer
+    }
+
+    // a panic becomes an error answer, the phase it happened in gets the time until then
+    pub fn run(&self, input: &str) -> Run {
+        let mut metrics = Metrics::default();
+        let start = Instant::now();
+        match panic::catch_unwind(AssertUnwindSafe(|| self.measure(input, &mut metrics))) {
+            Ok(answer) => Run { answer, panicked: false, metrics },
+            Err(payload) => {
+                if metrics.parse.is_zero() {
+                    metrics.parse = start.elapsed();
+                } else {
+                    metrics.solve = start.elapsed() - metrics.parse;
+                }
+                if payload.is::<Cancelled>() {
+                    return Run { answer: Err("cancelled".to_string()), panicked: false, metrics };
+                }
+                let msg = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                Run { answer: Err(format!("panicked: {}", msg)), panicked: true, metrics }
+            }
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self.variant {
+            Some(v) => format!("day{:02} part{} ({})", self.day, self.part, v),
+            None => format!("day{:02} part{}", self.day, self.part),
+        }
+    }
+
+    pub fn matches(&self, day: Option<u32>, part: Option<u8>) -> bool {
+        day.is_none_or(|d| d == self.day) && part.is_none_or(|p| p == self.part)
+    }
+}
+
+// where cargo-aoc used to keep the puzzle inputs
+pub fn input_path(day: u32) -> PathBuf {
+    PathBuf::from(format!("input/2022/day{}.txt", day))
+}
+
+// known answers next to the inputs, see report::read_answers
+pub fn answers_path() -> PathBuf {
+    PathBuf::from("input/2022/answers.txt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    pub fn test_registry() {
+        let entries = registry();
+        for day in 1..=25 {
+            for part in 1..=2 {
+                let main = entries.iter().filter(|e| e.matches(Some(day), Some(part)) && e.variant.is_none());
+                assert_eq!(main.count(), 1, "day {} part {}", day, part);
+            }
+        }
+        let mut names = entries.iter().map(Entry::name).collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), entries.len());
+    }
+
+    #[test]
+    pub fn test_solve() {
+        let entries = registry();
+        let find = |day, part, variant| entries.iter().find(|e| e.matches(Some(day), Some(part)) && e.variant == variant).unwrap();
+        assert_eq!(find(6, 2, Some("noset")).solve("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), Ok("19".to_string()));
+        assert_eq!(find(2, 1, None).solve("A Y\nB X\nC Z"), Ok("15".to_string()));
+        assert_eq!(find(2, 1, None).solve("A Q"), Err("invalid shape: \"Q\"".to_string()));
+        assert_eq!(find(25, 1, None).solve("1=\n2"), Ok("10".to_string()));
+        assert_eq!(find(18, 2, None).solve("2,2,2\n1,2,2"), Err("not solved yet".to_string()));
+    }
+
+    #[test]
+    pub fn test_run() {
+        let entries = registry();
+        let find = |day, part| entries.iter().find(|e| e.matches(Some(day), Some(part)) && e.variant.is_none()).unwrap();
+        let run = find(1, 1).run("1\n2\n\n4");
+        assert_eq!((run.answer, run.panicked), (Ok("4".to_string()), false));
+        // day01 parses numbers with unwrap
+        let run = find(1, 1).run("x");
+        assert!(run.panicked);
+        assert!(run.answer.unwrap_err().starts_with("panicked: "));
+        let run = find(2, 1).run("A Q");
+        assert_eq!((run.answer, run.panicked), (Err("invalid shape: \"Q\"".to_string()), false));
+    }
+}